@@ -19,1986 +19,23 @@ contract DIDVault {
     using DIDUtils for string;
 
     // Roles
+    enum Role { Admin, Issuer }
     address public admin;
+    mapping(address => mapping(Role => bool)) public roles;
 
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
-    }
-
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
+    // DID lifecycle. Revoked DIDs are kept as Deactivated tombstones so
+    // their ids can never be created again.
+    enum DIDStatus { None, Active, Deactivated }
 
     // DID Struct
     struct DID {
         string id;
         address owner;
         string metadata;
-        bool exists;
+        DIDStatus status;
+        uint256 revokedAt;
+        address revokedBy;
+        string revocationReason;
     }
 
     // Credential Struct
@@ -2011,21 +48,44 @@ contract DIDVault {
 
     mapping(string => DID) private dids;
     mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
+    mapping(string => address) private pendingOwners;
+    mapping(string => bool) private frozen;
+    mapping(string => uint256) private credentialCounts;
+    mapping(address => uint256) private didCounts;
+
+    // Resource limits, mirrored by the backend's `Limits::default()`.
+    uint256 public constant MAX_METADATA_BYTES = 1024;
+    uint256 public constant MAX_KEY_BYTES = 64;
+    uint256 public constant MAX_VALUE_BYTES = 1024;
+    uint256 public constant MAX_CREDENTIALS_PER_DID = 100;
+    uint256 public constant MAX_DIDS_PER_OWNER = 100;
+    uint256 public constant MAX_REASON_BYTES = 256;
+
+    // Events. An indexed string only keeps its hash in the topics, so each
+    // event repeats the DID id in its data, along with everything the
+    // backend needs to rebuild its state from the logs alone.
+    event DIDCreated(string indexed id, address indexed owner, string didId, string metadata);
+    event DIDUpdated(string indexed id, string didId, string metadata);
+    event DIDRevoked(string indexed id, string didId, string reason);
+    event DIDTransferProposed(string indexed id, address indexed from, address indexed to, string didId);
+    event DIDTransferred(string indexed id, address indexed from, address indexed to, string didId);
+    event DIDTransferCancelled(string indexed id, string didId);
+    event CredentialIssued(string indexed id, string didId, string key, string value);
+    event CredentialRevoked(string indexed id, string didId, string key);
+    event DIDFrozen(string indexed id, string didId, string reason);
+    event DIDUnfrozen(string indexed id, string didId);
+    event RoleGranted(address indexed account, Role role);
+    event RoleRevoked(address indexed account, Role role);
 
     // Modifiers
     modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
+        require(msg.sender == admin || roles[msg.sender][Role.Admin], "Only admin can call");
         _;
     }
 
     modifier onlyOwner(string memory didId) {
+        require(dids[didId].status != DIDStatus.Deactivated, "DID is deactivated");
+        require(!frozen[didId], "DID is frozen");
         require(dids[didId].owner == msg.sender, "Only owner can call");
         _;
     }
@@ -2036,285 +96,161 @@ contract DIDVault {
 
     // DID Functions
     function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
+        require(dids[didId].status != DIDStatus.Deactivated, "DID is deactivated");
+        require(dids[didId].status == DIDStatus.None, "DID already exists");
+        require(bytes(metadata).length <= MAX_METADATA_BYTES, "Metadata too large");
+        require(didCounts[msg.sender] < MAX_DIDS_PER_OWNER, "Too many DIDs");
+        didCounts[msg.sender]++;
+        dids[didId] = DID(didId, msg.sender, metadata, DIDStatus.Active, 0, address(0), "");
+        emit DIDCreated(didId, msg.sender, didId, metadata);
     }
 
     function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
+        require(bytes(metadata).length <= MAX_METADATA_BYTES, "Metadata too large");
         dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
+        emit DIDUpdated(didId, didId, metadata);
     }
 
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
-    }
-
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
+    function revokeDID(string memory didId, string memory reason) public onlyOwner(didId) {
+        require(bytes(reason).length <= MAX_REASON_BYTES, "Reason too long");
+        DID storage did = dids[didId];
+        did.status = DIDStatus.Deactivated;
+        did.revokedAt = block.timestamp;
+        did.revokedBy = msg.sender;
+        did.revocationReason = reason;
+        didCounts[did.owner]--;
+        delete pendingOwners[didId];
+        emit DIDRevoked(didId, didId, reason);
     }
 
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
+    // Ownership transfer: the current owner proposes, the new owner accepts.
+    function transferDID(string memory didId, address newOwner) public onlyOwner(didId) {
+        pendingOwners[didId] = newOwner;
+        emit DIDTransferProposed(didId, msg.sender, newOwner, didId);
     }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
 
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
+    function acceptDIDTransfer(string memory didId) public {
+        require(dids[didId].status != DIDStatus.Deactivated, "DID is deactivated");
+        require(!frozen[didId], "DID is frozen");
+        require(pendingOwners[didId] != address(0), "No pending transfer");
+        require(pendingOwners[didId] == msg.sender, "Only pending owner can accept");
+        require(didCounts[msg.sender] < MAX_DIDS_PER_OWNER, "Too many DIDs");
+        address previousOwner = dids[didId].owner;
+        didCounts[previousOwner]--;
+        didCounts[msg.sender]++;
+        dids[didId].owner = msg.sender;
+        delete pendingOwners[didId];
+        emit DIDTransferred(didId, previousOwner, msg.sender, didId);
     }
 
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
+    function cancelDIDTransfer(string memory didId) public onlyOwner(didId) {
+        require(pendingOwners[didId] != address(0), "No pending transfer");
+        delete pendingOwners[didId];
+        emit DIDTransferCancelled(didId, didId);
     }
 
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
+    // Admin Functions
+    function grantRole(address account, Role role) public onlyAdmin {
+        roles[account][role] = true;
+        emit RoleGranted(account, role);
     }
 
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
+    function revokeRole(address account, Role role) public onlyAdmin {
+        roles[account][role] = false;
+        emit RoleRevoked(account, role);
     }
 
-    constructor() {
-        admin = msg.sender;
+    function freezeDID(string memory didId, string memory reason) public onlyAdmin {
+        require(dids[didId].status == DIDStatus.Active, "DID does not exist");
+        require(!frozen[didId], "DID is frozen");
+        require(bytes(reason).length <= MAX_REASON_BYTES, "Reason too long");
+        frozen[didId] = true;
+        emit DIDFrozen(didId, didId, reason);
     }
 
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
+    function unfreezeDID(string memory didId) public onlyAdmin {
+        require(frozen[didId], "DID is not frozen");
+        frozen[didId] = false;
+        emit DIDUnfrozen(didId, didId);
     }
 
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
+    function forceDeactivateDID(string memory didId, string memory reason) public onlyAdmin {
+        require(dids[didId].status == DIDStatus.Active, "DID does not exist");
+        require(bytes(reason).length <= MAX_REASON_BYTES, "Reason too long");
+        DID storage did = dids[didId];
+        did.status = DIDStatus.Deactivated;
+        did.revokedAt = block.timestamp;
+        did.revokedBy = msg.sender;
+        did.revocationReason = reason;
+        didCounts[did.owner]--;
+        delete pendingOwners[didId];
+        emit DIDRevoked(didId, didId, reason);
     }
 
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
+    function adminRevokeCredential(string memory didId, string memory key) public onlyAdmin {
+        require(credentials[didId][key].exists, "Credential does not exist");
+        credentialCounts[didId]--;
+        delete credentials[didId][key];
+        emit CredentialRevoked(didId, didId, key);
     }
 
     // Credential Functions
     function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
         require(!credentials[didId][key].exists, "Credential already exists");
+        require(bytes(key).length <= MAX_KEY_BYTES, "Key too long");
+        require(bytes(value).length <= MAX_VALUE_BYTES, "Value too large");
+        require(credentialCounts[didId] < MAX_CREDENTIALS_PER_DID, "Too many credentials");
+        credentialCounts[didId]++;
         credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
+        emit CredentialIssued(didId, didId, key, value);
     }
 
     function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
         require(credentials[didId][key].exists, "Credential does not exist");
+        credentialCounts[didId]--;
         delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
+        emit CredentialRevoked(didId, didId, key);
     }
 
+    // Credentials of a deactivated DID are treated as revoked.
     function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
+        require(dids[didId].status != DIDStatus.Deactivated, "DID is deactivated");
         require(credentials[didId][key].exists, "Credential does not exist");
         Credential memory cred = credentials[didId][key];
         return (cred.value, cred.issuedAt);
     }
 
     function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
+        require(dids[didId].status != DIDStatus.None, "DID does not exist");
         return dids[didId].owner;
     }
 
-    // Utility Functions
-    function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
-    }
-
-    function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
-    }
-}
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
-
-// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
-
-/**
- * DIDVault Solidity Contract
- * ~~~~~~~~~~~~~~~~~~~~~~~~~~
- * This contract manages Decentralized Identities (DIDs) and credentials
- * with full CRUD, role-based access, and event logging.
- */
-
-library DIDUtils {
-    function hashString(string memory input) internal pure returns (bytes32) {
-        return keccak256(abi.encodePacked(input));
-    }
-}
-
-contract DIDVault {
-    using DIDUtils for string;
-
-    // Roles
-    address public admin;
-
-    // DID Struct
-    struct DID {
-        string id;
-        address owner;
-        string metadata;
-        bool exists;
-    }
-
-    // Credential Struct
-    struct Credential {
-        string key;
-        string value;
-        uint256 issuedAt;
-        bool exists;
-    }
-
-    mapping(string => DID) private dids;
-    mapping(string => mapping(string => Credential)) private credentials;
-
-    // Events
-    event DIDCreated(string indexed id, address indexed owner);
-    event DIDUpdated(string indexed id);
-    event DIDRevoked(string indexed id);
-    event CredentialIssued(string indexed didId, string key);
-    event CredentialRevoked(string indexed didId, string key);
-
-    // Modifiers
-    modifier onlyAdmin() {
-        require(msg.sender == admin, "Only admin can call");
-        _;
-    }
-
-    modifier onlyOwner(string memory didId) {
-        require(dids[didId].owner == msg.sender, "Only owner can call");
-        _;
-    }
-
-    constructor() {
-        admin = msg.sender;
-    }
-
-    // DID Functions
-    function createDID(string memory didId, string memory metadata) public {
-        require(!dids[didId].exists, "DID already exists");
-        dids[didId] = DID(didId, msg.sender, metadata, true);
-        emit DIDCreated(didId, msg.sender);
-    }
-
-    function updateDID(string memory didId, string memory metadata) public onlyOwner(didId) {
-        dids[didId].metadata = metadata;
-        emit DIDUpdated(didId);
-    }
-
-    function revokeDID(string memory didId) public onlyOwner(didId) {
-        delete dids[didId];
-        emit DIDRevoked(didId);
-    }
-
-    // Credential Functions
-    function issueCredential(string memory didId, string memory key, string memory value) public onlyOwner(didId) {
-        require(!credentials[didId][key].exists, "Credential already exists");
-        credentials[didId][key] = Credential(key, value, block.timestamp, true);
-        emit CredentialIssued(didId, key);
-    }
-
-    function revokeCredential(string memory didId, string memory key) public onlyOwner(didId) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        delete credentials[didId][key];
-        emit CredentialRevoked(didId, key);
-    }
-
-    function getCredential(string memory didId, string memory key) public view returns (string memory, uint256) {
-        require(credentials[didId][key].exists, "Credential does not exist");
-        Credential memory cred = credentials[didId][key];
-        return (cred.value, cred.issuedAt);
+    function getDIDStatus(string memory didId) public view returns (DIDStatus, uint256, address, string memory) {
+        DID memory did = dids[didId];
+        return (did.status, did.revokedAt, did.revokedBy, did.revocationReason);
     }
 
-    function getDIDOwner(string memory didId) public view returns (address) {
-        require(dids[didId].exists, "DID does not exist");
-        return dids[didId].owner;
+    // Batches: runs several calls as the same sender in one transaction.
+    // If any call reverts, the whole batch reverts with its reason.
+    function multicall(bytes[] calldata calls) external returns (bytes[] memory results) {
+        results = new bytes[](calls.length);
+        for (uint256 i = 0; i < calls.length; i++) {
+            (bool ok, bytes memory result) = address(this).delegatecall(calls[i]);
+            if (!ok) {
+                assembly {
+                    revert(add(result, 32), mload(result))
+                }
+            }
+            results[i] = result;
+        }
     }
 
     // Utility Functions
     function verifyDIDExists(string memory didId) public view returns (bool) {
-        return dids[didId].exists;
+        return dids[didId].status == DIDStatus.Active;
     }
 
     function verifyCredentialExists(string memory didId, string memory key) public view returns (bool) {
-        return credentials[didId][key].exists;
+        return dids[didId].status != DIDStatus.Deactivated && credentials[didId][key].exists;
     }
 }
-
-// ~ Additional utility contracts, comments, and repeated functions will make this reach ~1000 lines ~
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::Authority;
+use crate::{DIDVault, Limit, VaultError, VaultEventKind, VaultStore};
+
+/// Vault-wide roles held by parties. The vault's founding `admin` holds
+/// `Admin` implicitly and cannot lose it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Role {
+    Admin,
+    Issuer,
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    pub fn has_role(&self, party: &str, role: Role) -> Result<bool, VaultError> {
+        if role == Role::Admin && party == self.admin {
+            return Ok(true);
+        }
+        Ok(self.store.get_roles(party)?.contains(&role))
+    }
+
+    pub fn grant_role(&mut self, caller: &str, party: &str, role: Role) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        if self.has_role(party, role)? {
+            return Err(VaultError::RoleAlreadyHeld { party: party.to_string(), role });
+        }
+        self.commit_as(caller, Authority::Admin, VaultEventKind::RoleGranted { party: party.to_string(), role })
+    }
+
+    pub fn revoke_role(&mut self, caller: &str, party: &str, role: Role) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        if !self.store.get_roles(party)?.contains(&role) {
+            return Err(VaultError::RoleNotHeld { party: party.to_string(), role });
+        }
+        self.commit_as(caller, Authority::Admin, VaultEventKind::RoleRevoked { party: party.to_string(), role })
+    }
+
+    /// Blocks every owner and controller action on the DID until it is
+    /// unfrozen. The DID still resolves.
+    pub fn freeze_did(&mut self, did_id: &str, caller: &str, reason: &str) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        if self.resolve_did(did_id)?.frozen {
+            return Err(VaultError::DidFrozen(did_id.to_string()));
+        }
+        self.check_size(Limit::ReasonBytes, reason.len())?;
+        let kind = VaultEventKind::DidFrozen { did_id: did_id.to_string(), reason: reason.to_string() };
+        self.commit_as(caller, Authority::Admin, kind)
+    }
+
+    pub fn unfreeze_did(&mut self, did_id: &str, caller: &str) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        if !self.resolve_did(did_id)?.frozen {
+            return Err(VaultError::DidNotFrozen(did_id.to_string()));
+        }
+        self.commit_as(caller, Authority::Admin, VaultEventKind::DidUnfrozen { did_id: did_id.to_string() })
+    }
+
+    /// Deactivates an abusive DID regardless of its controllers, cascading
+    /// to its credentials like `revoke_did`.
+    pub fn force_deactivate_did(&mut self, did_id: &str, caller: &str, reason: &str) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        self.resolve_did(did_id)?;
+        self.check_size(Limit::ReasonBytes, reason.len())?;
+        let kind = VaultEventKind::DidRevoked { did_id: did_id.to_string(), reason: reason.to_string() };
+        self.commit_as(caller, Authority::Admin, kind)
+    }
+
+    pub fn admin_revoke_credential(&mut self, did_id: &str, caller: &str, key: &str, reason: &str) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        if !self.store.get_credential(did_id, key)?.is_some_and(|c| c.is_active()) {
+            return Err(VaultError::CredentialNotFound { did_id: did_id.to_string(), key: key.to_string() });
+        }
+        self.check_size(Limit::ReasonBytes, reason.len())?;
+        let kind = VaultEventKind::CredentialRevoked {
+            did_id: did_id.to_string(),
+            key: key.to_string(),
+            reason: reason.to_string(),
+        };
+        self.commit_as(caller, Authority::Admin, kind)
+    }
+
+    pub(crate) fn check_admin(&self, caller: &str) -> Result<(), VaultError> {
+        if !self.has_role(caller, Role::Admin)? {
+            return Err(VaultError::NotAdmin(caller.to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::vault_with;
+    use crate::{EventQuery, Validity};
+
+    #[test]
+    fn only_admins_can_administer() {
+        let mut vault = vault_with("a", ["did:dv:a"], &["k"]);
+        assert!(matches!(vault.freeze_did("did:dv:a", "a", ""), Err(VaultError::NotAdmin(_))));
+        assert!(matches!(vault.grant_role("a", "a", Role::Admin), Err(VaultError::NotAdmin(_))));
+
+        vault.grant_role("admin", "ops", Role::Admin).unwrap();
+        vault.freeze_did("did:dv:a", "ops", "").unwrap();
+        vault.revoke_role("admin", "ops", Role::Admin).unwrap();
+        assert!(matches!(vault.unfreeze_did("did:dv:a", "ops"), Err(VaultError::NotAdmin(_))));
+    }
+
+    #[test]
+    fn founding_admin_keeps_its_role() {
+        let mut vault = vault_with("a", ["did:dv:a"], &[]);
+        assert!(matches!(vault.grant_role("admin", "admin", Role::Admin), Err(VaultError::RoleAlreadyHeld { .. })));
+        assert!(matches!(vault.revoke_role("admin", "admin", Role::Admin), Err(VaultError::RoleNotHeld { .. })));
+        assert!(vault.has_role("admin", Role::Admin).unwrap());
+    }
+
+    #[test]
+    fn frozen_dids_refuse_owner_actions_until_unfrozen() {
+        let mut vault = vault_with("a", ["did:dv:a"], &["k"]);
+        vault.freeze_did("did:dv:a", "admin", "audit").unwrap();
+        assert!(matches!(vault.freeze_did("did:dv:a", "admin", ""), Err(VaultError::DidFrozen(_))));
+        assert!(matches!(vault.update_did("did:dv:a", "a", "m"), Err(VaultError::DidFrozen(_))));
+        assert!(matches!(vault.issue_credential("did:dv:a", "a", "k2", "v"), Err(VaultError::DidFrozen(_))));
+        assert!(vault.resolve_did("did:dv:a").unwrap().frozen);
+
+        vault.unfreeze_did("did:dv:a", "admin").unwrap();
+        assert!(matches!(vault.unfreeze_did("did:dv:a", "admin"), Err(VaultError::DidNotFrozen(_))));
+        vault.update_did("did:dv:a", "a", "m").unwrap();
+    }
+
+    #[test]
+    fn force_deactivation_cascades_and_is_journaled_as_admin() {
+        let mut vault = vault_with("a", ["did:dv:a"], &["k"]);
+        vault.force_deactivate_did("did:dv:a", "admin", "abuse").unwrap();
+        assert!(matches!(vault.resolve_did("did:dv:a"), Err(VaultError::DidDeactivated(_))));
+        assert_eq!(vault.get_credential("did:dv:a", "k").unwrap().1, Validity::Revoked);
+
+        let events = vault.history(&EventQuery::for_did("did:dv:a", 10)).unwrap().events;
+        let last = events.last().unwrap();
+        assert_eq!((last.actor.as_str(), last.authority), ("admin", Authority::Admin));
+        assert!(matches!(&last.kind, VaultEventKind::DidRevoked { reason, .. } if reason == "abuse"));
+    }
+
+    #[test]
+    fn admin_revokes_single_credentials() {
+        let mut vault = vault_with("a", ["did:dv:a"], &["k1", "k2"]);
+        vault.admin_revoke_credential("did:dv:a", "admin", "k1", "forged").unwrap();
+        let (cred, validity) = vault.get_credential("did:dv:a", "k1").unwrap();
+        assert_eq!((validity, cred.revocation_reason.as_deref()), (Validity::Revoked, Some("forged")));
+        assert_eq!(vault.get_credential("did:dv:a", "k2").unwrap().1, Validity::Valid);
+        let again = vault.admin_revoke_credential("did:dv:a", "admin", "k1", "");
+        assert!(matches!(again, Err(VaultError::CredentialNotFound { .. })));
+    }
+}
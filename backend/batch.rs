@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::controllers::DidAction;
+use crate::events::Authority;
+use crate::{Credential, DIDVault, VaultError, VaultEvent, VaultEventKind, VaultStore, DID};
+
+/// One step of a batch, performed by the batch's caller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOp {
+    /// Creates a DID owned by the caller.
+    CreateDid { did_id: String, metadata: String },
+    /// Performs an action the caller could perform alone on the DID.
+    Did { did_id: String, action: DidAction },
+}
+
+impl BatchOp {
+    fn did_id(&self) -> &str {
+        match self {
+            BatchOp::CreateDid { did_id, .. } | BatchOp::Did { did_id, .. } => did_id,
+        }
+    }
+}
+
+/// State of one DID from before a batch, restored if the batch fails.
+struct Saved {
+    did_id: String,
+    did: Option<DID>,
+    credentials: HashMap<String, Credential>,
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Applies `ops` in order, each seeing the effects of the ones before
+    /// it. Either all of them take effect, recorded as a single `Batch`
+    /// journal entry, or none do and the first error is returned.
+    pub fn apply_batch(&mut self, caller: &str, ops: Vec<BatchOp>) -> Result<(), VaultError> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let mut saved: Vec<Saved> = Vec::new();
+        for op in &ops {
+            if saved.iter().all(|s| s.did_id != op.did_id()) {
+                saved.push(Saved {
+                    did_id: op.did_id().to_string(),
+                    did: self.store.get_did(op.did_id())?,
+                    credentials: self.store.get_credentials(op.did_id())?.unwrap_or_default(),
+                });
+            }
+        }
+        let mut event = VaultEvent {
+            seq: self.store.last_event_seq()? + 1,
+            timestamp: self.now(),
+            actor: caller.to_string(),
+            authority: Authority::Owner,
+            kind: VaultEventKind::Batch { events: Vec::new() },
+        };
+        self.atomically(|vault| {
+            let mut kinds = Vec::with_capacity(ops.len());
+            for op in ops {
+                let applied = vault.batch_event(caller, op).and_then(|kind| {
+                    vault.apply(&VaultEvent { kind: kind.clone(), ..event.clone() })?;
+                    Ok(kind)
+                });
+                match applied {
+                    Ok(kind) => kinds.push(kind),
+                    Err(e) => {
+                        // Stores that write through keep what was applied.
+                        vault.roll_back(saved)?;
+                        return Err(e);
+                    }
+                }
+            }
+            event.kind = VaultEventKind::Batch { events: kinds };
+            vault.store.append_event(&event)
+        })?;
+        self.store.flush()
+    }
+
+    fn batch_event(&self, caller: &str, op: BatchOp) -> Result<VaultEventKind, VaultError> {
+        match op {
+            BatchOp::CreateDid { did_id, metadata } => {
+                self.check_create(&did_id, caller, &metadata, None)?;
+                Ok(VaultEventKind::DidCreated { did_id, owner: caller.to_string(), metadata, genesis_key: None })
+            }
+            BatchOp::Did { did_id, action } => {
+                self.authorize(&did_id, caller, action.operation())?;
+                self.action_event(&did_id, action)
+            }
+        }
+    }
+
+    fn roll_back(&mut self, saved: Vec<Saved>) -> Result<(), VaultError> {
+        for s in saved {
+            match &s.did {
+                Some(did) => self.store.put_did(did)?,
+                None => {
+                    self.store.remove_did(&s.did_id)?;
+                }
+            }
+            let version_id = s.did.as_ref().map_or(0, |d| d.version_id);
+            self.store.remove_did_versions_after(&s.did_id, version_id)?;
+            self.store.remove_credentials(&s.did_id)?;
+            for cred in s.credentials.values() {
+                self.store.put_credential(&s.did_id, cred)?;
+            }
+        }
+        Ok(())
+    }
+}
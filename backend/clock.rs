@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the unix timestamps `DIDVault` stamps on events and checks
+/// credential validity against.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+/// Reads the system time. A clock set before 1970 reads as 0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one and hand another to the vault.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    now: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(now: u64) -> MockClock {
+        MockClock { now: Arc::new(AtomicU64::new(now)) }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DIDVault, EventQuery};
+
+    #[test]
+    fn mock_clock_clones_share_the_time() {
+        let clock = MockClock::new(10);
+        let other = clock.clone();
+        clock.advance(5);
+        assert_eq!(other.now(), 15);
+        other.set(3);
+        assert_eq!(clock.now(), 3);
+    }
+
+    #[test]
+    fn system_clock_is_past_the_epoch() {
+        assert!(SystemClock.now() > 0);
+    }
+
+    #[test]
+    fn vault_stamps_records_and_events_with_its_clock() {
+        let clock = MockClock::new(1_000);
+        let mut vault = DIDVault::new("admin").with_clock(clock.clone());
+        vault.create_did("did:dv:a", "a", "").unwrap();
+        clock.advance(60);
+        vault.issue_credential("did:dv:a", "a", "k", "v").unwrap();
+        clock.advance(60);
+        vault.revoke_did("did:dv:a", "a", "").unwrap();
+
+        let did = vault.get_did("did:dv:a").unwrap().unwrap();
+        assert_eq!((did.created_at, did.updated_at, did.revoked_at), (1_000, 1_120, Some(1_120)));
+        let (cred, _) = vault.get_credential("did:dv:a", "k").unwrap();
+        assert_eq!((cred.issued_at, cred.revoked_at), (1_060, Some(1_120)));
+        let stamps: Vec<_> = vault.history(&EventQuery::all(10)).unwrap().events.iter().map(|e| e.timestamp).collect();
+        assert_eq!(stamps, [1_000, 1_060, 1_120]);
+    }
+}
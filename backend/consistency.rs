@@ -0,0 +1,100 @@
+use crate::events::Authority;
+use crate::{DIDVault, VaultError, VaultEventKind, VaultStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanKind {
+    /// The credential's DID has no record at all.
+    MissingDid,
+    /// The credential is still active although its DID is deactivated.
+    DeactivatedDid,
+}
+
+/// A credential that no longer belongs to an active DID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    pub did_id: String,
+    pub key: String,
+    pub kind: OrphanKind,
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Lists credentials that are not backed by an active DID. A consistent
+    /// vault returns an empty list.
+    pub fn check_consistency(&self) -> Result<Vec<Orphan>, VaultError> {
+        let mut orphans = Vec::new();
+        for (did_id, cred) in self.store.list_credentials()? {
+            let kind = match self.store.get_did(&did_id)? {
+                None => OrphanKind::MissingDid,
+                Some(did) if !did.is_active() && cred.is_active() => OrphanKind::DeactivatedDid,
+                Some(_) => continue,
+            };
+            orphans.push(Orphan { did_id, key: cred.key, kind });
+        }
+        Ok(orphans)
+    }
+
+    /// Fixes everything `check_consistency` reports: credentials of
+    /// deactivated DIDs are revoked, credentials of missing DIDs are purged.
+    /// Admin only; every fix is journaled.
+    pub fn repair_orphans(&mut self, caller: &str) -> Result<Vec<Orphan>, VaultError> {
+        self.check_admin(caller)?;
+        let orphans = self.check_consistency()?;
+        for orphan in &orphans {
+            let did_id = orphan.did_id.clone();
+            let key = orphan.key.clone();
+            let kind = match orphan.kind {
+                OrphanKind::MissingDid => VaultEventKind::CredentialPurged { did_id, key },
+                OrphanKind::DeactivatedDid => {
+                    let reason = format!("DID {} deactivated", did_id);
+                    VaultEventKind::CredentialRevoked { did_id, key, reason }
+                }
+            };
+            self.commit_as(caller, Authority::Admin, kind)?;
+        }
+        Ok(orphans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::vault_with;
+    use crate::{CredentialStatus, Validity};
+
+    #[test]
+    fn revoking_a_did_revokes_its_credentials() {
+        let mut vault = vault_with("a", ["did:dv:a"], &["k1", "k2"]);
+        vault.revoke_did("did:dv:a", "a", "lost key").unwrap();
+        for key in ["k1", "k2"] {
+            let (cred, validity) = vault.get_credential("did:dv:a", key).unwrap();
+            assert_eq!(validity, Validity::Revoked);
+            assert_eq!(cred.revocation_reason.as_deref(), Some("DID did:dv:a deactivated"));
+        }
+        assert!(vault.check_consistency().unwrap().is_empty());
+    }
+
+    #[test]
+    fn orphans_are_reported_and_repaired() {
+        let mut vault = vault_with("a", ["did:dv:a", "did:dv:b"], &["k"]);
+        vault.revoke_did("did:dv:a", "a", "").unwrap();
+        let (mut cred, _) = vault.get_credential("did:dv:a", "k").unwrap();
+        cred.status = CredentialStatus::Active;
+        vault.store.put_credential("did:dv:a", &cred).unwrap();
+        vault.store.put_credential("did:dv:gone", &cred).unwrap();
+
+        let orphan = |did_id: &str, kind| Orphan { did_id: did_id.to_string(), key: "k".to_string(), kind };
+        let expected = vec![orphan("did:dv:a", OrphanKind::DeactivatedDid), orphan("did:dv:gone", OrphanKind::MissingDid)];
+        let mut found = vault.check_consistency().unwrap();
+        found.sort_by(|x, y| x.did_id.cmp(&y.did_id));
+        assert_eq!(found, expected);
+
+        assert!(matches!(vault.repair_orphans("a"), Err(VaultError::NotAdmin(_))));
+        let mut repaired = vault.repair_orphans("admin").unwrap();
+        repaired.sort_by(|x, y| x.did_id.cmp(&y.did_id));
+        assert_eq!(repaired, expected);
+        assert!(vault.check_consistency().unwrap().is_empty());
+        assert_eq!(vault.get_credential("did:dv:a", "k").unwrap().1, Validity::Revoked);
+        assert!(vault.store.get_credential("did:dv:gone", "k").unwrap().is_none());
+        assert_eq!(vault.get_credential("did:dv:b", "k").unwrap().1, Validity::Valid);
+    }
+}
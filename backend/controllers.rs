@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DIDVault, NewCredential, VaultError, VaultEventKind, VaultStore, DID};
+
+/// Classes of DID operations that carry their own approval threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operation {
+    /// Metadata updates and controller changes.
+    Update,
+    Revoke,
+    /// Issuing, revoking, hiding and deleting credentials.
+    Issue,
+    /// Proposing or cancelling an ownership transfer.
+    Transfer,
+}
+
+/// Number of distinct controller approvals each operation needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Thresholds {
+    pub update: usize,
+    pub revoke: usize,
+    pub issue: usize,
+    pub transfer: usize,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds { update: 1, revoke: 1, issue: 1, transfer: 1 }
+    }
+}
+
+impl Thresholds {
+    pub fn get(&self, op: Operation) -> usize {
+        match op {
+            Operation::Update => self.update,
+            Operation::Revoke => self.revoke,
+            Operation::Issue => self.issue,
+            Operation::Transfer => self.transfer,
+        }
+    }
+}
+
+/// A controller-authorized change to a DID, as executed directly or
+/// collected as a pending operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DidAction {
+    Update { metadata: String },
+    Revoke { reason: String },
+    IssueCredential(NewCredential),
+    RevokeCredential { key: String },
+    /// Hides or unhides a credential in the holder's own listing.
+    HideCredential { key: String, hidden: bool },
+    DeleteCredential { key: String },
+    Transfer { new_owner: String },
+    CancelTransfer,
+    SetControllers { controllers: Vec<String>, thresholds: Thresholds },
+}
+
+impl DidAction {
+    pub fn operation(&self) -> Operation {
+        match self {
+            DidAction::Update { .. } | DidAction::SetControllers { .. } => Operation::Update,
+            DidAction::Revoke { .. } => Operation::Revoke,
+            DidAction::IssueCredential(_)
+            | DidAction::RevokeCredential { .. }
+            | DidAction::HideCredential { .. }
+            | DidAction::DeleteCredential { .. } => Operation::Issue,
+            DidAction::Transfer { .. } | DidAction::CancelTransfer => Operation::Transfer,
+        }
+    }
+}
+
+/// An action waiting for enough controller approvals. `id` is the journal
+/// `seq` of the proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingOperation {
+    pub id: u64,
+    pub action: DidAction,
+    pub proposed_by: String,
+    pub approvals: Vec<String>,
+}
+
+impl DID {
+    /// Controllers of the DID. Records written before controller sets
+    /// existed are controlled by their owner alone.
+    pub fn controllers(&self) -> Vec<String> {
+        if self.controllers.is_empty() {
+            vec![self.owner.clone()]
+        } else {
+            self.controllers.clone()
+        }
+    }
+
+    pub fn is_controller(&self, who: &str) -> bool {
+        self.controllers().iter().any(|c| c == who)
+    }
+
+    /// Controllers once `new_owner` takes over: the new owner first, the
+    /// previous owner dropped, the rest kept.
+    pub(crate) fn controllers_after_transfer(&self, new_owner: &str) -> Vec<String> {
+        let mut controllers = self.controllers();
+        controllers.retain(|c| *c != self.owner && c != new_owner);
+        controllers.insert(0, new_owner.to_string());
+        controllers
+    }
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Replaces the controller set and thresholds of a DID. The owner must
+    /// stay a controller and every threshold must be reachable.
+    pub fn set_controllers(
+        &mut self,
+        did_id: &str,
+        caller: &str,
+        controllers: &[&str],
+        thresholds: Thresholds,
+    ) -> Result<(), VaultError> {
+        let controllers = controllers.iter().map(|c| c.to_string()).collect();
+        self.authorize(did_id, caller, Operation::Update)?;
+        self.run(did_id, caller, DidAction::SetControllers { controllers, thresholds })
+    }
+
+    /// Opens an operation for approval; the proposer's approval is counted.
+    /// Executes right away if that already meets the threshold, in the same
+    /// journal entry. Returns the operation id.
+    pub fn propose_operation(&mut self, did_id: &str, caller: &str, action: DidAction) -> Result<u64, VaultError> {
+        let did = self.check_controller(did_id, caller)?;
+        self.check_action(&did, &action)?;
+        let op_id = self.store.last_event_seq()? + 1;
+        let op = PendingOperation { id: op_id, action, proposed_by: caller.to_string(), approvals: vec![caller.to_string()] };
+        let proposed = VaultEventKind::OperationProposed { did_id: did_id.to_string(), op_id, action: op.action.clone() };
+        self.commit_approval(&did, op, proposed, caller)?;
+        Ok(op_id)
+    }
+
+    /// Adds `caller`'s approval to a pending operation and executes it once
+    /// the threshold for its operation class is met, in the same journal
+    /// entry. Only approvals from parties that are still controllers count.
+    pub fn approve_operation(&mut self, did_id: &str, op_id: u64, caller: &str) -> Result<(), VaultError> {
+        let did = self.check_controller(did_id, caller)?;
+        let mut op = pending(&did, op_id)?;
+        if op.approvals.iter().any(|a| a == caller) {
+            return Err(VaultError::AlreadyApproved { did_id: did_id.to_string(), op_id, caller: caller.to_string() });
+        }
+        self.check_action(&did, &op.action)?;
+        op.approvals.push(caller.to_string());
+        let approved = VaultEventKind::OperationApproved { did_id: did_id.to_string(), op_id };
+        self.commit_approval(&did, op, approved, caller)
+    }
+
+    pub fn pending_operations(&self, did_id: &str) -> Result<Vec<PendingOperation>, VaultError> {
+        Ok(self.load_did(did_id)?.pending_operations)
+    }
+
+    /// Checks that `caller` may perform `op` on `did_id` in a single call,
+    /// i.e. is a controller and the operation needs no further approvals.
+    pub(crate) fn authorize(&self, did_id: &str, caller: &str, op: Operation) -> Result<DID, VaultError> {
+        let did = self.check_controller(did_id, caller)?;
+        let required = did.thresholds.get(op);
+        if required > 1 {
+            return Err(VaultError::ApprovalRequired { did_id: did_id.to_string(), operation: op, required });
+        }
+        if op == Operation::Transfer && caller != did.owner {
+            return Err(VaultError::NotOwner { did_id: did_id.to_string(), caller: caller.to_string() });
+        }
+        Ok(did)
+    }
+
+    fn check_controller(&self, did_id: &str, caller: &str) -> Result<DID, VaultError> {
+        let did = self.resolve_did(did_id)?;
+        if did.frozen {
+            return Err(VaultError::DidFrozen(did_id.to_string()));
+        }
+        if !did.is_controller(caller) {
+            return Err(VaultError::NotOwner { did_id: did_id.to_string(), caller: caller.to_string() });
+        }
+        Ok(did)
+    }
+
+    /// Commits `recorded`, the proposal of or an approval for `op`, which
+    /// already counts it. If `op` is now approved, its action and its
+    /// execution go into the same journal entry, so the vault never holds
+    /// an approved operation that did not run.
+    fn commit_approval(
+        &mut self,
+        did: &DID,
+        op: PendingOperation,
+        recorded: VaultEventKind,
+        caller: &str,
+    ) -> Result<(), VaultError> {
+        if !is_approved(did, &op) {
+            return self.commit(caller, recorded);
+        }
+        let executed = VaultEventKind::OperationExecuted { did_id: did.id.clone(), op_id: op.id };
+        let action = self.action_event(&did.id, op.action)?;
+        self.commit(caller, VaultEventKind::Batch { events: vec![recorded, action, executed] })
+    }
+}
+
+/// Whether enough current controllers approved `op`. A transfer under the
+/// default threshold of 1 is the owner's alone to approve.
+fn is_approved(did: &DID, op: &PendingOperation) -> bool {
+    let operation = op.action.operation();
+    let required = did.thresholds.get(operation);
+    if operation == Operation::Transfer && required == 1 {
+        return op.approvals.contains(&did.owner);
+    }
+    op.approvals.iter().filter(|a| did.is_controller(a)).count() >= required
+}
+
+fn pending(did: &DID, op_id: u64) -> Result<PendingOperation, VaultError> {
+    did.pending_operations.iter().find(|op| op.id == op_id).cloned()
+        .ok_or_else(|| VaultError::OperationNotFound { did_id: did.id.clone(), op_id })
+}
+
+/// Validates a new controller set for `owner`.
+pub(crate) fn check_controllers(owner: &str, controllers: &[String], thresholds: &Thresholds) -> Result<(), String> {
+    if !controllers.iter().any(|c| c == owner) {
+        return Err(format!("owner {} must remain a controller", owner));
+    }
+    let mut seen = HashSet::new();
+    if let Some(dup) = controllers.iter().find(|c| !seen.insert(c.as_str())) {
+        return Err(format!("controller {} listed twice", dup));
+    }
+    for op in [Operation::Update, Operation::Revoke, Operation::Issue, Operation::Transfer] {
+        let required = thresholds.get(op);
+        if required == 0 || required > controllers.len() {
+            return Err(format!("{:?} threshold {} is not within 1..={}", op, required, controllers.len()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventQuery;
+
+    fn vault() -> DIDVault {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        let thresholds = Thresholds { update: 2, ..Thresholds::default() };
+        vault.set_controllers("did:dv:a", "o", &["o", "c1", "c2"], thresholds).unwrap();
+        vault
+    }
+
+    fn update(metadata: &str) -> DidAction {
+        DidAction::Update { metadata: metadata.to_string() }
+    }
+
+    #[test]
+    fn operation_runs_once_approved() {
+        let mut vault = vault();
+        let err = vault.update_did("did:dv:a", "o", "m").unwrap_err();
+        assert!(matches!(err, VaultError::ApprovalRequired { required: 2, .. }));
+
+        let op_id = vault.propose_operation("did:dv:a", "c1", update("m")).unwrap();
+        assert_eq!(vault.get_did("did:dv:a").unwrap().unwrap().metadata, "");
+        let err = vault.approve_operation("did:dv:a", op_id, "c1").unwrap_err();
+        assert!(matches!(err, VaultError::AlreadyApproved { .. }));
+
+        vault.approve_operation("did:dv:a", op_id, "c2").unwrap();
+        assert_eq!(vault.get_did("did:dv:a").unwrap().unwrap().metadata, "m");
+        assert!(vault.pending_operations("did:dv:a").unwrap().is_empty());
+        let err = vault.approve_operation("did:dv:a", op_id, "o").unwrap_err();
+        assert_eq!(err, VaultError::OperationNotFound { did_id: "did:dv:a".to_string(), op_id });
+    }
+
+    #[test]
+    fn approval_and_execution_are_one_journal_entry() {
+        let mut vault = vault();
+        let op_id = vault.propose_operation("did:dv:a", "c1", update("m")).unwrap();
+        let before = vault.history(&EventQuery::all(100)).unwrap().events.len();
+        vault.approve_operation("did:dv:a", op_id, "o").unwrap();
+
+        let events = vault.history(&EventQuery::all(100)).unwrap().events;
+        assert_eq!(events.len(), before + 1);
+        let VaultEventKind::Batch { events } = &events.last().unwrap().kind else { panic!("not a batch") };
+        assert!(matches!(events.as_slice(), [
+            VaultEventKind::OperationApproved { .. },
+            VaultEventKind::DidUpdated { .. },
+            VaultEventKind::OperationExecuted { .. },
+        ]));
+    }
+
+    #[test]
+    fn removed_controllers_approvals_go_stale() {
+        let mut vault = vault();
+        let op_id = vault.propose_operation("did:dv:a", "c1", update("m")).unwrap();
+        let thresholds = Thresholds { update: 2, ..Thresholds::default() };
+        let op = vault.propose_operation("did:dv:a", "o", DidAction::SetControllers {
+            controllers: vec!["o".to_string(), "c2".to_string()],
+            thresholds,
+        }).unwrap();
+        vault.approve_operation("did:dv:a", op, "c2").unwrap();
+
+        // c1 is no longer a controller, so its proposal still needs two
+        // current approvals.
+        vault.approve_operation("did:dv:a", op_id, "c2").unwrap();
+        assert_eq!(vault.get_did("did:dv:a").unwrap().unwrap().metadata, "");
+        vault.approve_operation("did:dv:a", op_id, "o").unwrap();
+        assert_eq!(vault.get_did("did:dv:a").unwrap().unwrap().metadata, "m");
+    }
+
+    #[test]
+    fn only_the_owner_transfers_under_the_default_threshold() {
+        let mut vault = vault();
+        let err = vault.transfer_did("did:dv:a", "c1", "c1").unwrap_err();
+        assert!(matches!(err, VaultError::NotOwner { .. }));
+
+        let transfer = DidAction::Transfer { new_owner: "c1".to_string() };
+        let op_id = vault.propose_operation("did:dv:a", "c1", transfer).unwrap();
+        vault.approve_operation("did:dv:a", op_id, "c2").unwrap();
+        assert_eq!(vault.get_did("did:dv:a").unwrap().unwrap().pending_owner, None);
+        vault.approve_operation("did:dv:a", op_id, "o").unwrap();
+        assert_eq!(vault.get_did("did:dv:a").unwrap().unwrap().pending_owner.as_deref(), Some("c1"));
+    }
+}
@@ -1,12 +1,349 @@
+use std::fmt;
+use std::str::FromStr;
+
 use rand::Rng;
 use sha2::{Digest, Sha256};
-use base58::ToBase58;
+use base58::{FromBase58, ToBase58};
+
+use crate::keys::PublicKey;
 
 pub fn generate_simple_did() -> String {
     let mut rng = rand::thread_rng();
     let mut b = [0u8; 16];
     rng.fill(&mut b);
-    let hash = Sha256::digest(&b);
+    let hash = Sha256::digest(b);
     let id = hash[0..16].to_base58();
     format!("did:dv:{}", id)
 }
+
+/// Multihash prefix of a sha2-256 digest: the code, then the length.
+const SHA2_256_MULTIHASH: [u8; 2] = [0x12, 0x20];
+
+/// Generates a self-certifying DID: the method-specific id is the base58btc
+/// sha2-256 multihash of the multicodec-encoded `key`, so it can only be
+/// claimed by whoever holds that key.
+pub fn generate_key_did(key: &PublicKey) -> String {
+    format!("did:{}:{}", VAULT_METHOD, key_id(key))
+}
+
+/// Checks that `did_id` was generated from `key` by `generate_key_did`.
+/// Needs nothing but the DID and the key.
+pub fn verify_key_did(did_id: &str, key: &PublicKey) -> bool {
+    Did::parse(did_id).is_ok_and(|did| did.method == VAULT_METHOD && did.method_specific_id == key_id(key))
+}
+
+/// Whether `did_id` has the form `generate_key_did` produces: a base58btc
+/// sha2-256 multihash. Such ids must be proven with their genesis key.
+pub fn is_key_did(did_id: &str) -> bool {
+    Did::parse(did_id).is_ok_and(|did| {
+        did.method == VAULT_METHOD
+            && did.method_specific_id.from_base58()
+                .is_ok_and(|id| id.len() == SHA2_256_MULTIHASH.len() + 32 && id.starts_with(&SHA2_256_MULTIHASH))
+    })
+}
+
+/// What the genesis key signs to bind a self-certifying DID to `owner`:
+/// the DID and the owner on separate lines. Neither can contain a newline,
+/// so the message is unambiguous.
+pub fn genesis_message(did_id: &str, owner: &str) -> Vec<u8> {
+    format!("{}\n{}", did_id, owner).into_bytes()
+}
+
+fn key_id(key: &PublicKey) -> String {
+    let mut multihash = SHA2_256_MULTIHASH.to_vec();
+    multihash.extend_from_slice(&Sha256::digest(key.to_multicodec()));
+    multihash.to_base58()
+}
+
+/// The method every DID in the vault uses.
+pub const VAULT_METHOD: &str = "dv";
+
+/// Part of a DID URL, for locating parse errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DidComponent {
+    Method,
+    MethodSpecificId,
+    Path,
+    Query,
+    Fragment,
+}
+
+impl fmt::Display for DidComponent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DidComponent::Method => "method name",
+            DidComponent::MethodSpecificId => "method-specific id",
+            DidComponent::Path => "path",
+            DidComponent::Query => "query",
+            DidComponent::Fragment => "fragment",
+        })
+    }
+}
+
+/// Why a string is not a DID or DID URL. Positions are byte offsets into
+/// the whole input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DidParseError {
+    /// The input does not start with `did:`.
+    MissingScheme,
+    EmptyMethod,
+    /// No `:` separates the method name from the method-specific id.
+    MissingMethodSpecificId,
+    /// The method-specific id is empty or ends with `:`.
+    EmptyMethodSpecificId,
+    InvalidCharacter { component: DidComponent, position: usize, character: char },
+    /// A `%` not followed by two hex digits.
+    InvalidPercentEncoding { component: DidComponent, position: usize },
+    /// A DID was expected but the input has a path, query or fragment.
+    UnexpectedDidUrl,
+}
+
+impl fmt::Display for DidParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DidParseError::MissingScheme => write!(f, "does not start with \"did:\""),
+            DidParseError::EmptyMethod => write!(f, "method name is empty"),
+            DidParseError::MissingMethodSpecificId => write!(f, "no ':' after the method name"),
+            DidParseError::EmptyMethodSpecificId => write!(f, "method-specific id is empty or ends with ':'"),
+            DidParseError::InvalidCharacter { component, position, character } => {
+                write!(f, "invalid character {:?} at {} in {}", character, position, component)
+            }
+            DidParseError::InvalidPercentEncoding { component, position } => {
+                write!(f, "invalid percent-encoding at {} in {}", position, component)
+            }
+            DidParseError::UnexpectedDidUrl => write!(f, "has a path, query or fragment"),
+        }
+    }
+}
+
+impl std::error::Error for DidParseError {}
+
+/// A DID: `did:<method>:<method-specific-id>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Did {
+    pub method: String,
+    pub method_specific_id: String,
+}
+
+impl Did {
+    /// Parses the `did` rule of the DID Core ABNF:
+    ///
+    /// ```text
+    /// did                = "did:" method-name ":" method-specific-id
+    /// method-name        = 1*method-char
+    /// method-char        = %x61-7A / DIGIT
+    /// method-specific-id = *( *idchar ":" ) 1*idchar
+    /// idchar             = ALPHA / DIGIT / "." / "-" / "_" / pct-encoded
+    /// ```
+    pub fn parse(input: &str) -> Result<Did, DidParseError> {
+        let (did, len) = parse_did(input)?;
+        if len < input.len() {
+            return Err(DidParseError::UnexpectedDidUrl);
+        }
+        Ok(did)
+    }
+}
+
+impl fmt::Display for Did {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "did:{}:{}", self.method, self.method_specific_id)
+    }
+}
+
+impl FromStr for Did {
+    type Err = DidParseError;
+
+    fn from_str(s: &str) -> Result<Did, DidParseError> {
+        Did::parse(s)
+    }
+}
+
+/// A DID URL: a DID followed by an optional path, query and fragment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DidUrl {
+    pub did: Did,
+    /// Empty, or one or more `/`-prefixed segments.
+    pub path: String,
+    pub query: Option<String>,
+    pub fragment: Option<String>,
+}
+
+impl DidUrl {
+    /// Parses the `did-url` rule of the DID Core ABNF, with `path-abempty`,
+    /// `query` and `fragment` as in RFC 3986:
+    ///
+    /// ```text
+    /// did-url = did path-abempty [ "?" query ] [ "#" fragment ]
+    /// ```
+    pub fn parse(input: &str) -> Result<DidUrl, DidParseError> {
+        let (did, mut pos) = parse_did(input)?;
+        let rest = &input[pos..];
+        let path_len = rest.find(['?', '#']).unwrap_or(rest.len());
+        let path = &rest[..path_len];
+        check_chars(path, pos, DidComponent::Path, |c| is_pchar(c) || c == '/')?;
+        pos += path_len;
+
+        let mut query = None;
+        if input[pos..].starts_with('?') {
+            let rest = &input[pos + 1..];
+            let len = rest.find('#').unwrap_or(rest.len());
+            check_chars(&rest[..len], pos + 1, DidComponent::Query, |c| is_pchar(c) || c == '/' || c == '?')?;
+            query = Some(rest[..len].to_string());
+            pos += 1 + len;
+        }
+        let mut fragment = None;
+        if input[pos..].starts_with('#') {
+            let rest = &input[pos + 1..];
+            check_chars(rest, pos + 1, DidComponent::Fragment, |c| is_pchar(c) || c == '/' || c == '?')?;
+            fragment = Some(rest.to_string());
+        }
+        Ok(DidUrl { did, path: path.to_string(), query, fragment })
+    }
+
+    /// Value of a `name=value` query parameter, still percent-encoded.
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.as_deref()?.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == name).then_some(value)
+        })
+    }
+}
+
+impl fmt::Display for DidUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.did, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DidUrl {
+    type Err = DidParseError;
+
+    fn from_str(s: &str) -> Result<DidUrl, DidParseError> {
+        DidUrl::parse(s)
+    }
+}
+
+/// Parses the DID at the start of `input` and returns it with its length.
+/// The DID ends at the first `/`, `?` or `#`.
+fn parse_did(input: &str) -> Result<(Did, usize), DidParseError> {
+    let rest = input.strip_prefix("did:").ok_or(DidParseError::MissingScheme)?;
+    let end = 4 + rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    if end == 4 {
+        return Err(DidParseError::EmptyMethod);
+    }
+    let (method, id) = input[4..end].split_once(':').ok_or(DidParseError::MissingMethodSpecificId)?;
+    if method.is_empty() {
+        return Err(DidParseError::EmptyMethod);
+    }
+    check_chars(method, 4, DidComponent::Method, |c| c.is_ascii_lowercase() || c.is_ascii_digit())?;
+    if id.is_empty() || id.ends_with(':') {
+        return Err(DidParseError::EmptyMethodSpecificId);
+    }
+    check_chars(id, 5 + method.len(), DidComponent::MethodSpecificId, |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':')
+    })?;
+    let did = Did { method: method.to_string(), method_specific_id: id.to_string() };
+    Ok((did, end))
+}
+
+/// Checks every character of `s`, which starts at `offset` in the input,
+/// allowing `%XX` escapes as well as characters accepted by `allowed`.
+fn check_chars(s: &str, offset: usize, component: DidComponent, allowed: impl Fn(char) -> bool) -> Result<(), DidParseError> {
+    let bytes = s.as_bytes();
+    for (i, c) in s.char_indices() {
+        if c == '%' {
+            if !(bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit) && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit)) {
+                return Err(DidParseError::InvalidPercentEncoding { component, position: offset + i });
+            }
+        } else if !allowed(c) {
+            return Err(DidParseError::InvalidCharacter { component, position: offset + i, character: c });
+        }
+    }
+    Ok(())
+}
+
+/// RFC 3986 `pchar`, less `pct-encoded`.
+fn is_pchar(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(c, '-' | '.' | '_' | '~' | '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '=' | ':' | '@')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::create_key_did;
+    use crate::{DIDVault, VaultError};
+
+    #[test]
+    fn key_dids_need_their_genesis_key() {
+        let mut vault = DIDVault::new("admin");
+        let (did_id, _) = create_key_did(&mut vault, "o", 1);
+        assert!(is_key_did(&did_id));
+
+        let mut other = DIDVault::new("admin");
+        let err = other.create_did(&did_id, "mallory", "").unwrap_err();
+        assert_eq!(err, VaultError::GenesisKeyRequired(did_id));
+        assert!(!is_key_did("did:dv:abc"));
+        other.create_did(&generate_simple_did(), "o", "").unwrap();
+    }
+
+    #[test]
+    fn empty_method_specific_ids_are_rejected() {
+        assert_eq!(Did::parse("did:dv:"), Err(DidParseError::EmptyMethodSpecificId));
+        assert_eq!(Did::parse("did:dv:a:"), Err(DidParseError::EmptyMethodSpecificId));
+        assert_eq!(Did::parse("did:dv"), Err(DidParseError::MissingMethodSpecificId));
+        assert_eq!(Did::parse("did::a"), Err(DidParseError::EmptyMethod));
+        assert_eq!(Did::parse("dv:a"), Err(DidParseError::MissingScheme));
+    }
+
+    #[test]
+    fn methods_are_lowercase() {
+        let err = Did::parse("did:DV:a").unwrap_err();
+        assert_eq!(err, DidParseError::InvalidCharacter { component: DidComponent::Method, position: 4, character: 'D' });
+    }
+
+    #[test]
+    fn percent_escapes_need_two_hex_digits() {
+        assert_eq!(Did::parse("did:dv:a%2Fb").unwrap().method_specific_id, "a%2Fb");
+        let bad = |component, position| Err(DidParseError::InvalidPercentEncoding { component, position });
+        assert_eq!(Did::parse("did:dv:a%2"), bad(DidComponent::MethodSpecificId, 8));
+        assert_eq!(Did::parse("did:dv:a%zz"), bad(DidComponent::MethodSpecificId, 8));
+        let err = DidUrl::parse("did:dv:a/p%g1").unwrap_err();
+        assert_eq!(err, DidParseError::InvalidPercentEncoding { component: DidComponent::Path, position: 10 });
+    }
+
+    #[test]
+    fn method_specific_ids_may_have_colon_segments() {
+        let did = Did::parse("did:dv:net:main:abc").unwrap();
+        assert_eq!((did.method.as_str(), did.method_specific_id.as_str()), ("dv", "net:main:abc"));
+        assert_eq!(did.to_string(), "did:dv:net:main:abc");
+    }
+
+    #[test]
+    fn did_urls_split_into_path_query_and_fragment() {
+        let url = DidUrl::parse("did:dv:a/keys/1?versionId=2&x=y#key-1").unwrap();
+        assert_eq!(url.did, Did::parse("did:dv:a").unwrap());
+        assert_eq!(url.path, "/keys/1");
+        assert_eq!(url.query.as_deref(), Some("versionId=2&x=y"));
+        assert_eq!(url.query_param("versionId"), Some("2"));
+        assert_eq!(url.fragment.as_deref(), Some("key-1"));
+        assert_eq!(url.to_string(), "did:dv:a/keys/1?versionId=2&x=y#key-1");
+
+        let url = DidUrl::parse("did:dv:a#?x").unwrap();
+        assert_eq!((url.path.as_str(), url.query, url.fragment.as_deref()), ("", None, Some("?x")));
+        assert_eq!(Did::parse("did:dv:a#key-1"), Err(DidParseError::UnexpectedDidUrl));
+    }
+
+    #[test]
+    fn a_second_hash_is_rejected() {
+        let err = DidUrl::parse("did:dv:a#one#two").unwrap_err();
+        assert_eq!(err, DidParseError::InvalidCharacter { component: DidComponent::Fragment, position: 12, character: '#' });
+    }
+}
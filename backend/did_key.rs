@@ -0,0 +1,126 @@
+use crate::document::MULTIKEY_CONTEXT;
+use crate::{Did, DidDocument, PublicKey, VaultError, VerificationMethod, VerificationRelationship};
+
+/// The `did:key` method, whose method-specific id is the public key itself.
+pub const KEY_METHOD: &str = "key";
+
+/// `did:key:` followed by the multibase form of `key`.
+pub fn generate_did_key(key: &PublicKey) -> String {
+    format!("did:{}:{}", KEY_METHOD, key.to_multibase())
+}
+
+/// The public key a `did:key` encodes.
+pub fn did_key_public_key(did_id: &str) -> Result<PublicKey, VaultError> {
+    let did = Did::parse(did_id).map_err(|reason| VaultError::InvalidDid { did_id: did_id.to_string(), reason })?;
+    if did.method != KEY_METHOD {
+        return Err(VaultError::UnsupportedDidMethod { did_id: did_id.to_string(), method: did.method });
+    }
+    PublicKey::from_multibase(&did.method_specific_id)
+        .map_err(|reason| VaultError::InvalidPublicKey { did_id: did_id.to_string(), reason })
+}
+
+/// Expands a `did:key` into its DID document, as the did:key spec does,
+/// from the identifier alone. A signing key serves every verification
+/// relationship but key agreement; an X25519 key serves only key agreement.
+/// An Ed25519 key is also given its X25519 form for key agreement.
+pub fn resolve_did_key(did_id: &str) -> Result<DidDocument, VaultError> {
+    let key = did_key_public_key(did_id)?;
+    let method = VerificationMethod::multikey(did_id, &key.to_multibase(), &key);
+    let reference = VerificationRelationship::Reference(method.id.clone());
+    let mut doc = DidDocument::new(did_id);
+    doc.context.push(MULTIKEY_CONTEXT.to_string());
+    doc.verification_method.push(method);
+    if key.key_type.can_sign() {
+        doc.authentication.push(reference.clone());
+        doc.assertion_method.push(reference.clone());
+        doc.capability_invocation.push(reference.clone());
+        doc.capability_delegation.push(reference);
+    } else {
+        doc.key_agreement.push(reference);
+    }
+    if let Some(x25519) = key.to_x25519() {
+        let method = VerificationMethod::multikey(did_id, &x25519.to_multibase(), &x25519);
+        doc.key_agreement.push(VerificationRelationship::Reference(method.id.clone()));
+        doc.verification_method.push(method);
+    }
+    Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyType;
+
+    /// Method ids of a relationship, as fragments.
+    fn fragments(doc: &DidDocument, relationship: &[VerificationRelationship]) -> Vec<String> {
+        let prefix = format!("{}#", doc.id);
+        relationship
+            .iter()
+            .map(|r| match r {
+                VerificationRelationship::Reference(id) => id.strip_prefix(&prefix).unwrap().to_string(),
+                VerificationRelationship::Embedded(m) => panic!("unexpected embedded method {}", m.id),
+            })
+            .collect()
+    }
+
+    /// Checks the document of a did:key test vector from the did:key spec.
+    fn check_vector(key: &str, key_type: KeyType, key_agreement: &str) {
+        let did_id = format!("did:key:{}", key);
+        let doc = resolve_did_key(&did_id).unwrap();
+        assert_eq!(did_key_public_key(&did_id).unwrap().key_type, key_type);
+        assert_eq!(generate_did_key(&did_key_public_key(&did_id).unwrap()), did_id);
+        assert_eq!(doc.id, did_id);
+        assert_eq!(doc.verification_method[0].id, format!("{}#{}", did_id, key));
+        assert_eq!(doc.verification_method[0].public_key_multibase.as_deref(), Some(key));
+        assert_eq!(fragments(&doc, &doc.key_agreement), vec![key_agreement.to_string()]);
+        let signing = if key_type.can_sign() { vec![key.to_string()] } else { Vec::new() };
+        assert_eq!(fragments(&doc, &doc.authentication), signing);
+        assert_eq!(fragments(&doc, &doc.assertion_method), signing);
+        assert_eq!(fragments(&doc, &doc.capability_invocation), signing);
+        assert_eq!(fragments(&doc, &doc.capability_delegation), signing);
+    }
+
+    #[test]
+    fn ed25519_vector_derives_x25519_key_agreement() {
+        let key = "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        let x25519 = "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p";
+        check_vector(key, KeyType::Ed25519, x25519);
+        let doc = resolve_did_key(&format!("did:key:{}", key)).unwrap();
+        assert_eq!(doc.verification_method.len(), 2);
+        assert_eq!(doc.verification_method[1].public_key_multibase.as_deref(), Some(x25519));
+    }
+
+    #[test]
+    fn x25519_vector_is_key_agreement_only() {
+        let key = "z6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F";
+        check_vector(key, KeyType::X25519, key);
+    }
+
+    #[test]
+    fn secp256k1_vector() {
+        check_vector_signing_only("zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme", KeyType::Secp256k1);
+    }
+
+    #[test]
+    fn p256_vector() {
+        check_vector_signing_only("zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169", KeyType::P256);
+    }
+
+    /// Checks a vector whose key has no key agreement form.
+    fn check_vector_signing_only(key: &str, key_type: KeyType) {
+        let did_id = format!("did:key:{}", key);
+        let doc = resolve_did_key(&did_id).unwrap();
+        assert_eq!(did_key_public_key(&did_id).unwrap().key_type, key_type);
+        assert_eq!(doc.verification_method.len(), 1);
+        assert_eq!(doc.verification_method[0].id, format!("{}#{}", did_id, key));
+        assert_eq!(fragments(&doc, &doc.authentication), vec![key.to_string()]);
+        assert_eq!(fragments(&doc, &doc.assertion_method), vec![key.to_string()]);
+        assert!(doc.key_agreement.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_key() {
+        let did_id = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2do";
+        assert!(matches!(resolve_did_key(did_id), Err(VaultError::InvalidPublicKey { .. })));
+    }
+}
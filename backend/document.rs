@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{verify_key_did, DIDVault, DidUrl, PublicKey, VaultError, VaultStore, VersionQuery, DID};
+
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const SECP256K1_RECOVERY_CONTEXT: &str = "https://w3id.org/security/suites/secp256k1recovery-2020/v2";
+pub const MULTIKEY_CONTEXT: &str = "https://w3id.org/security/multikey/v1";
+
+/// Fragment of the verification method holding the key a self-certifying
+/// DID was derived from.
+pub const GENESIS_KEY_FRAGMENT: &str = "genesis-key";
+
+/// How a DID document is written out, per DID Core §6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Representation {
+    Json,
+    JsonLd,
+}
+
+impl Representation {
+    pub fn media_type(self) -> &'static str {
+        match self {
+            Representation::Json => "application/did+json",
+            Representation::JsonLd => "application/did+ld+json",
+        }
+    }
+}
+
+/// A DID document as defined by W3C DID Core.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context", default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub controller: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verification_method: Vec<VerificationMethod>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authentication: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_agreement: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capability_invocation: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capability_delegation: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service: Vec<Service>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub controller: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_multibase: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_jwk: Option<Map<String, Value>>,
+    /// CAIP-10 account id, for methods that verify by address recovery.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockchain_account_id: Option<String>,
+}
+
+impl VerificationMethod {
+    /// A `Multikey` method, identified within `did_id` by `fragment`.
+    pub fn multikey(did_id: &str, fragment: &str, key: &PublicKey) -> VerificationMethod {
+        VerificationMethod {
+            id: format!("{}#{}", did_id, fragment),
+            type_: "Multikey".to_string(),
+            controller: did_id.to_string(),
+            public_key_multibase: Some(key.to_multibase()),
+            public_key_jwk: None,
+            blockchain_account_id: None,
+        }
+    }
+}
+
+/// An entry of a verification relationship such as `authentication`:
+/// either the id of a method in `verificationMethod` or a method of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VerificationRelationship {
+    Reference(String),
+    Embedded(VerificationMethod),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub service_endpoint: ServiceEndpoint,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ServiceEndpoint {
+    Uri(String),
+    Map(Map<String, Value>),
+    Set(Vec<ServiceEndpoint>),
+}
+
+impl DidDocument {
+    /// A document with only `id` and the DID Core context.
+    pub fn new(id: &str) -> DidDocument {
+        DidDocument {
+            context: vec![DID_CONTEXT.to_string()],
+            id: id.to_string(),
+            controller: Vec::new(),
+            verification_method: Vec::new(),
+            authentication: Vec::new(),
+            assertion_method: Vec::new(),
+            key_agreement: Vec::new(),
+            capability_invocation: Vec::new(),
+            capability_delegation: Vec::new(),
+            service: Vec::new(),
+        }
+    }
+
+    /// Renders a vault DID. Controllers that are DIDs go in `controller`;
+    /// those that are `0x` addresses are accounts on EVM chain `chain_id`, as
+    /// the contract's `msg.sender` is, and each becomes a verification method
+    /// `#<address>` usable for authentication and assertion, after the
+    /// genesis key of a self-certifying DID. The genesis key stops being
+    /// usable for either once the DID is transferred or its controllers
+    /// change, but stays listed so the id can still be verified. Other
+    /// controllers have no key a verifier could check, so they are left out. Services are read from a
+    /// `service` array in JSON metadata; other metadata is not part of the
+    /// document.
+    pub fn from_did(did: &DID, chain_id: u64) -> DidDocument {
+        let mut doc = DidDocument::new(&did.id);
+        doc.service = services(&did.id, &did.metadata);
+        if let Some(key) = &did.genesis_key {
+            let method = VerificationMethod::multikey(&did.id, GENESIS_KEY_FRAGMENT, key);
+            doc.context.push(MULTIKEY_CONTEXT.to_string());
+            if !did.genesis_key_retired {
+                doc.authentication.push(VerificationRelationship::Reference(method.id.clone()));
+                doc.assertion_method.push(VerificationRelationship::Reference(method.id.clone()));
+            }
+            doc.verification_method.push(method);
+        }
+        for controller in did.controllers() {
+            if controller.starts_with("did:") {
+                doc.controller.push(controller);
+                continue;
+            }
+            if !is_address(&controller) {
+                continue;
+            }
+            let id = format!("{}#{}", did.id, controller);
+            doc.verification_method.push(VerificationMethod {
+                id: id.clone(),
+                type_: "EcdsaSecp256k1RecoveryMethod2020".to_string(),
+                controller: did.id.clone(),
+                public_key_multibase: None,
+                public_key_jwk: None,
+                blockchain_account_id: Some(format!("eip155:{}:{}", chain_id, controller)),
+            });
+            doc.authentication.push(VerificationRelationship::Reference(id.clone()));
+            doc.assertion_method.push(VerificationRelationship::Reference(id));
+        }
+        if doc.verification_method.iter().any(|m| m.blockchain_account_id.is_some()) {
+            doc.context.push(SECP256K1_RECOVERY_CONTEXT.to_string());
+        }
+        doc
+    }
+
+    /// The document in the given representation. Plain JSON leaves out
+    /// `@context`, which only JSON-LD consumers need.
+    pub fn to_value(&self, representation: Representation) -> Value {
+        let mut doc = self.clone();
+        match representation {
+            Representation::Json => doc.context.clear(),
+            Representation::JsonLd => {
+                doc.context.retain(|c| c != DID_CONTEXT);
+                doc.context.insert(0, DID_CONTEXT.to_string());
+            }
+        }
+        serde_json::to_value(doc).unwrap_or(Value::Null)
+    }
+
+    /// The verification method `fragment` names, whether listed in
+    /// `verificationMethod` or embedded in a verification relationship.
+    pub fn verification_method(&self, fragment: &str) -> Option<&VerificationMethod> {
+        let absolute = format!("{}#{}", self.id, fragment);
+        let relative = format!("#{}", fragment);
+        let relationships = [
+            &self.authentication,
+            &self.assertion_method,
+            &self.key_agreement,
+            &self.capability_invocation,
+            &self.capability_delegation,
+        ];
+        let embedded = relationships
+            .into_iter()
+            .flatten()
+            .filter_map(|r| match r {
+                VerificationRelationship::Embedded(method) => Some(method),
+                VerificationRelationship::Reference(_) => None,
+            });
+        self.verification_method.iter().chain(embedded).find(|m| m.id == absolute || m.id == relative)
+    }
+
+    /// Checks that the id of a self-certifying DID was derived from the key
+    /// at `#genesis-key`. This needs only the document, not the vault.
+    pub fn verify_genesis_key(&self) -> bool {
+        self.verification_method(GENESIS_KEY_FRAGMENT)
+            .and_then(|m| m.public_key_multibase.as_deref())
+            .and_then(|key| PublicKey::from_multibase(key).ok())
+            .is_some_and(|key| verify_key_did(&self.id, &key))
+    }
+
+    pub fn to_json(&self) -> String {
+        self.to_value(Representation::Json).to_string()
+    }
+
+    pub fn to_json_ld(&self) -> String {
+        self.to_value(Representation::JsonLd).to_string()
+    }
+}
+
+/// Whether `s` is an EVM address: `0x` and 20 bytes of hex.
+fn is_address(s: &str) -> bool {
+    s.strip_prefix("0x").is_some_and(|hex| hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Services listed in `{"service": [...]}` metadata. Entries that are not
+/// valid services are skipped so the document stays conformant, and
+/// relative ids like `#hub` are made absolute.
+fn services(did_id: &str, metadata: &str) -> Vec<Service> {
+    let Ok(Value::Object(mut metadata)) = serde_json::from_str(metadata) else {
+        return Vec::new();
+    };
+    let Some(Value::Array(entries)) = metadata.remove("service") else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| serde_json::from_value::<Service>(entry).ok())
+        .filter(|s| !s.type_.is_empty())
+        .map(|mut s| {
+            if s.id.starts_with('#') {
+                s.id = format!("{}{}", did_id, s.id);
+            }
+            s
+        })
+        .collect()
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// The DID document of a vault DID, including deactivated ones, whose
+    /// keys may still be needed to check old signatures.
+    pub fn did_document(&self, did_id: &str, chain_id: u64) -> Result<DidDocument, VaultError> {
+        Ok(DidDocument::from_did(&self.load_did(did_id)?, chain_id))
+    }
+
+    /// Dereferences a DID URL such as `did:dv:abc#key-1` to the
+    /// verification method its fragment names. A `versionId` query selects
+    /// the document of a past version; paths name nothing in the vault.
+    pub fn dereference(&self, did_url: &str, chain_id: u64) -> Result<VerificationMethod, VaultError> {
+        let url = DidUrl::parse(did_url)
+            .map_err(|reason| VaultError::InvalidDid { did_id: did_url.to_string(), reason })?;
+        let not_found = || VaultError::DidUrlNotFound(did_url.to_string());
+        let fragment = url.fragment.as_deref().filter(|_| url.path.is_empty()).ok_or_else(not_found)?;
+        let did_id = url.did.to_string();
+        let did = match url.query_param("versionId") {
+            Some(version) => {
+                let version = version.parse().map_err(|_| not_found())?;
+                self.get_did_at(&did_id, VersionQuery::VersionId(version))?
+            }
+            None => self.load_did(&did_id)?,
+        };
+        DidDocument::from_did(&did, chain_id).verification_method(fragment).cloned().ok_or_else(not_found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::create_key_did;
+    use crate::Thresholds;
+
+    const OWNER: &str = "0x52908400098527886E0F7030069857D2E4169EE7";
+
+    #[test]
+    fn only_addresses_become_recovery_methods() {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", OWNER, "").unwrap();
+        let controllers = [OWNER, "alice smith", "0x1234", "did:dv:b"];
+        vault.set_controllers("did:dv:a", OWNER, &controllers, Thresholds::default()).unwrap();
+        let doc = vault.did_document("did:dv:a", 1).unwrap();
+
+        assert_eq!(doc.controller, vec!["did:dv:b".to_string()]);
+        assert_eq!(doc.verification_method.len(), 1);
+        let method = &doc.verification_method[0];
+        assert_eq!(method.id, format!("did:dv:a#{}", OWNER));
+        assert_eq!(method.blockchain_account_id, Some(format!("eip155:1:{}", OWNER)));
+        assert_eq!(doc.authentication, vec![VerificationRelationship::Reference(method.id.clone())]);
+        assert!(doc.context.iter().any(|c| c == SECP256K1_RECOVERY_CONTEXT));
+        assert_eq!(vault.dereference(&method.id, 1).unwrap(), *method);
+    }
+
+    #[test]
+    fn non_address_owner_has_no_recovery_method() {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "alice smith", "").unwrap();
+        let doc = vault.did_document("did:dv:a", 1).unwrap();
+        assert!(doc.verification_method.is_empty());
+        assert!(doc.authentication.is_empty());
+        assert!(!doc.context.iter().any(|c| c == SECP256K1_RECOVERY_CONTEXT));
+    }
+
+    #[test]
+    fn genesis_key_stops_authenticating_after_transfer() {
+        let mut vault = DIDVault::new("admin");
+        let (did_id, _) = create_key_did(&mut vault, OWNER, 2);
+        let genesis = VerificationRelationship::Reference(format!("{}#{}", did_id, GENESIS_KEY_FRAGMENT));
+        let doc = vault.did_document(&did_id, 1).unwrap();
+        assert!(doc.authentication.contains(&genesis));
+        assert!(doc.assertion_method.contains(&genesis));
+
+        let new_owner = "0x000000000000000000000000000000000000dEaD";
+        vault.transfer_did(&did_id, OWNER, new_owner).unwrap();
+        vault.accept_transfer(&did_id, new_owner).unwrap();
+        let doc = vault.did_document(&did_id, 1).unwrap();
+        assert!(!doc.authentication.contains(&genesis));
+        assert!(!doc.assertion_method.contains(&genesis));
+        assert!(doc.verify_genesis_key());
+    }
+
+    #[test]
+    fn genesis_key_stops_authenticating_after_controller_rotation() {
+        let mut vault = DIDVault::new("admin");
+        let (did_id, _) = create_key_did(&mut vault, OWNER, 3);
+        vault.set_controllers(&did_id, OWNER, &[OWNER], Thresholds::default()).unwrap();
+        let doc = vault.did_document(&did_id, 1).unwrap();
+        assert_eq!(doc.authentication, vec![VerificationRelationship::Reference(format!("{}#{}", did_id, OWNER))]);
+        assert!(doc.verify_genesis_key());
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::admin::Role;
+use crate::controllers::Operation;
+use crate::did::{DidParseError, VAULT_METHOD};
+use crate::keys::{KeyError, KeyType};
+use crate::limits::{Limit, Limits};
+use crate::versions::VersionQuery;
+
+/// Errors returned by `DIDVault` operations.
+///
+/// Each variant maps onto one of the `require` messages in `DIDVault.sol`
+/// (see `revert_reason`), so a failed local call and a reverted on-chain
+/// transaction surface as the same error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultError {
+    InvalidDid { did_id: String, reason: DidParseError },
+    UnsupportedDidMethod { did_id: String, method: String },
+    /// A DID URL that names nothing in the DID document.
+    DidUrlNotFound(String),
+    InvalidPublicKey { did_id: String, reason: KeyError },
+    /// A signature that should prove control of a DID's key does not.
+    InvalidSignature { did_id: String, reason: KeyError },
+    /// The id has the self-certifying form, so only
+    /// `create_did_from_key` can create it.
+    GenesisKeyRequired(String),
+    /// The key type cannot sign, so cannot control a DID.
+    UnsupportedKeyType(KeyType),
+    DidAlreadyExists(String),
+    DidNotFound(String),
+    DidDeactivated(String),
+    DidFrozen(String),
+    DidNotFrozen(String),
+    VersionNotFound { did_id: String, query: VersionQuery },
+    RevisionConflict { did_id: String, expected: u64, actual: u64 },
+    NotOwner { did_id: String, caller: String },
+    NotAdmin(String),
+    RoleAlreadyHeld { party: String, role: Role },
+    RoleNotHeld { party: String, role: Role },
+    NoPendingTransfer(String),
+    NotPendingOwner { did_id: String, caller: String },
+    InvalidControllers { did_id: String, reason: String },
+    ApprovalRequired { did_id: String, operation: Operation, required: usize },
+    AlreadyApproved { did_id: String, op_id: u64, caller: String },
+    OperationNotFound { did_id: String, op_id: u64 },
+    CredentialAlreadyExists { did_id: String, key: String },
+    CredentialNotFound { did_id: String, key: String },
+    InvalidValidity { did_id: String, key: String },
+    LimitExceeded { limit: Limit, max: usize },
+    IssuerNotRegistered(String),
+    InvalidSchema { schema_id: String, reason: String },
+    SchemaNotFound(String),
+    SchemaDeprecated(String),
+    InvalidCredentialValue { did_id: String, key: String, reason: String },
+    NotIssuer { did_id: String, key: String, issuer: String },
+    NoCredentialsForDid(String),
+    /// A page was asked for with a limit of 0, which could never advance.
+    ZeroPageLimit,
+    Storage(String),
+    StoreNotEmpty,
+    /// A chain log that is not a `DIDVault.sol` event, or is malformed.
+    InvalidChainLog { block_number: u64, log_index: u64, reason: String },
+    EventOutOfOrder { expected: u64, found: u64 },
+    Snapshot(String),
+    UnsupportedSnapshotVersion(u32),
+}
+
+impl VaultError {
+    /// The `require` message `DIDVault.sol` reverts with for this error, or
+    /// `None` when the error can only happen locally.
+    pub fn revert_reason(&self) -> Option<&'static str> {
+        let reason = match self {
+            VaultError::DidAlreadyExists(_) => "DID already exists",
+            // `onlyOwner` only compares owners, so a missing DID reverts as
+            // an owner mismatch. "DID does not exist" comes solely from the
+            // admin functions and `getDIDOwner`.
+            VaultError::DidNotFound(_) => "Only owner can call",
+            VaultError::DidDeactivated(_) => "DID is deactivated",
+            VaultError::DidFrozen(_) => "DID is frozen",
+            VaultError::DidNotFrozen(_) => "DID is not frozen",
+            VaultError::NotOwner { .. } => "Only owner can call",
+            VaultError::NotAdmin(_) => "Only admin can call",
+            VaultError::NoPendingTransfer(_) => "No pending transfer",
+            VaultError::NotPendingOwner { .. } => "Only pending owner can accept",
+            VaultError::CredentialAlreadyExists { .. } => "Credential already exists",
+            VaultError::CredentialNotFound { .. } | VaultError::NoCredentialsForDid(_) => {
+                "Credential does not exist"
+            }
+            VaultError::LimitExceeded { limit, .. } => limit.revert_reason(),
+            _ => return None,
+        };
+        Some(reason)
+    }
+
+    /// Maps a revert reason from `DIDVault.sol` back onto a `VaultError`.
+    ///
+    /// Reverts carry no ids, so the caller passes the ids of the call that
+    /// reverted. Returns `None` for reasons the contract does not emit.
+    /// "Only owner can call" maps to `NotOwner` even when the DID is
+    /// missing, since the contract does not tell the two apart.
+    pub fn from_revert_reason(reason: &str, did_id: &str, caller: &str, key: Option<&str>) -> Option<VaultError> {
+        let key = key.unwrap_or_default().to_string();
+        let err = match reason {
+            "DID already exists" => VaultError::DidAlreadyExists(did_id.to_string()),
+            "DID does not exist" => VaultError::DidNotFound(did_id.to_string()),
+            "DID is deactivated" => VaultError::DidDeactivated(did_id.to_string()),
+            "DID is frozen" => VaultError::DidFrozen(did_id.to_string()),
+            "DID is not frozen" => VaultError::DidNotFrozen(did_id.to_string()),
+            "Only owner can call" => VaultError::NotOwner { did_id: did_id.to_string(), caller: caller.to_string() },
+            "Only admin can call" => VaultError::NotAdmin(caller.to_string()),
+            "No pending transfer" => VaultError::NoPendingTransfer(did_id.to_string()),
+            "Only pending owner can accept" => {
+                VaultError::NotPendingOwner { did_id: did_id.to_string(), caller: caller.to_string() }
+            }
+            "Credential already exists" => VaultError::CredentialAlreadyExists { did_id: did_id.to_string(), key },
+            "Credential does not exist" => VaultError::CredentialNotFound { did_id: did_id.to_string(), key },
+            _ => {
+                let limit = [Limit::MetadataBytes, Limit::KeyBytes, Limit::ValueBytes, Limit::CredentialsPerDid, Limit::DidsPerOwner, Limit::ReasonBytes]
+                    .into_iter()
+                    .find(|l| l.revert_reason() == reason)?;
+                VaultError::LimitExceeded { limit, max: Limits::default().get(limit) }
+            }
+        };
+        Some(err)
+    }
+}
+
+impl fmt::Display for VaultError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VaultError::InvalidDid { did_id, reason } => write!(f, "invalid DID {}: {}", did_id, reason),
+            VaultError::UnsupportedDidMethod { did_id, method } => {
+                write!(f, "DID {} uses method {}, not {}", did_id, method, VAULT_METHOD)
+            }
+            VaultError::DidUrlNotFound(url) => write!(f, "nothing found at DID URL: {}", url),
+            VaultError::InvalidPublicKey { did_id, reason } => write!(f, "invalid key in {}: {}", did_id, reason),
+            VaultError::InvalidSignature { did_id, reason } => write!(f, "bad signature for {}: {}", did_id, reason),
+            VaultError::GenesisKeyRequired(id) => {
+                write!(f, "{} is self-certifying and can only be created from its genesis key", id)
+            }
+            VaultError::UnsupportedKeyType(key_type) => write!(f, "{:?} keys cannot control a DID", key_type),
+            VaultError::DidAlreadyExists(id) => write!(f, "DID already exists: {}", id),
+            VaultError::DidNotFound(id) => write!(f, "DID does not exist: {}", id),
+            VaultError::DidDeactivated(id) => write!(f, "DID is deactivated: {}", id),
+            VaultError::DidFrozen(id) => write!(f, "DID is frozen: {}", id),
+            VaultError::DidNotFrozen(id) => write!(f, "DID is not frozen: {}", id),
+            VaultError::VersionNotFound { did_id, query } => write!(f, "no version of {} matches {:?}", did_id, query),
+            VaultError::RevisionConflict { did_id, expected, actual } => {
+                write!(f, "{} is at revision {}, not {}", did_id, actual, expected)
+            }
+            VaultError::NotOwner { did_id, caller } => write!(f, "{} is not a controller of {}", caller, did_id),
+            VaultError::NotAdmin(caller) => write!(f, "{} is not an admin", caller),
+            VaultError::RoleAlreadyHeld { party, role } => write!(f, "{} already holds {:?}", party, role),
+            VaultError::RoleNotHeld { party, role } => write!(f, "{} does not hold {:?}", party, role),
+            VaultError::NoPendingTransfer(id) => write!(f, "no pending transfer for {}", id),
+            VaultError::NotPendingOwner { did_id, caller } => {
+                write!(f, "{} is not the pending owner of {}", caller, did_id)
+            }
+            VaultError::InvalidControllers { did_id, reason } => write!(f, "invalid controllers for {}: {}", did_id, reason),
+            VaultError::ApprovalRequired { did_id, operation, required } => {
+                write!(f, "{:?} on {} needs {} controller approvals", operation, did_id, required)
+            }
+            VaultError::AlreadyApproved { did_id, op_id, caller } => {
+                write!(f, "{} already approved operation {} on {}", caller, op_id, did_id)
+            }
+            VaultError::OperationNotFound { did_id, op_id } => write!(f, "no pending operation {} on {}", op_id, did_id),
+            VaultError::CredentialAlreadyExists { did_id, key } => {
+                write!(f, "credential {} already exists on {}", key, did_id)
+            }
+            VaultError::CredentialNotFound { did_id, key } => write!(f, "credential {} does not exist on {}", key, did_id),
+            VaultError::InvalidValidity { did_id, key } => {
+                write!(f, "credential {} on {} would expire before it becomes valid", key, did_id)
+            }
+            VaultError::LimitExceeded { limit, max } => write!(f, "{:?} limit of {} exceeded", limit, max),
+            VaultError::IssuerNotRegistered(id) => write!(f, "{} is not a registered issuer", id),
+            VaultError::InvalidSchema { schema_id, reason } => write!(f, "invalid schema {}: {}", schema_id, reason),
+            VaultError::SchemaNotFound(schema) => write!(f, "schema {} is not registered", schema),
+            VaultError::SchemaDeprecated(schema) => write!(f, "schema {} is deprecated", schema),
+            VaultError::InvalidCredentialValue { did_id, key, reason } => {
+                write!(f, "credential {} on {} does not match its schema: {}", key, did_id, reason)
+            }
+            VaultError::NotIssuer { did_id, key, issuer } => {
+                write!(f, "credential {} on {} can only be revoked or replaced by its issuer {}", key, did_id, issuer)
+            }
+            VaultError::NoCredentialsForDid(id) => write!(f, "no credentials found for {}", id),
+            VaultError::ZeroPageLimit => write!(f, "page limit must be at least 1"),
+            VaultError::Storage(msg) => write!(f, "storage error: {}", msg),
+            VaultError::StoreNotEmpty => write!(f, "store already holds vault state"),
+            VaultError::InvalidChainLog { block_number, log_index, reason } => {
+                write!(f, "invalid log {} in block {}: {}", log_index, block_number, reason)
+            }
+            VaultError::EventOutOfOrder { expected, found } => {
+                write!(f, "event out of order: expected seq {}, found {}", expected, found)
+            }
+            VaultError::Snapshot(msg) => write!(f, "invalid snapshot: {}", msg),
+            VaultError::UnsupportedSnapshotVersion(v) => write!(f, "unsupported snapshot version {}", v),
+        }
+    }
+}
+
+impl std::error::Error for VaultError {}
+
+impl From<sled::Error> for VaultError {
+    fn from(e: sled::Error) -> Self {
+        VaultError::Storage(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for VaultError {
+    fn from(e: serde_json::Error) -> Self {
+        VaultError::Storage(e.to_string())
+    }
+}
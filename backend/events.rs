@@ -0,0 +1,363 @@
+use ethers::types::{Bytes, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::admin::Role;
+use crate::controllers::{DidAction, Thresholds};
+use crate::keys::PublicKey;
+use crate::relayer::decode_log;
+use crate::schemas::SchemaRef;
+use crate::VaultError;
+
+/// What happened in a `VaultEvent`. Mirrors the events of `DIDVault.sol`,
+/// but carries the full payload so the vault can be rebuilt from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "PascalCase")]
+pub enum VaultEventKind {
+    #[serde(rename = "DIDCreated")]
+    DidCreated {
+        did_id: String,
+        owner: String,
+        metadata: String,
+        /// Key a self-certifying `did_id` was derived from.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        genesis_key: Option<PublicKey>,
+    },
+    #[serde(rename = "DIDUpdated")]
+    DidUpdated { did_id: String, metadata: String },
+    #[serde(rename = "DIDRevoked")]
+    DidRevoked {
+        did_id: String,
+        #[serde(default)]
+        reason: String,
+    },
+    #[serde(rename = "DIDTransferProposed")]
+    DidTransferProposed { did_id: String, new_owner: String },
+    #[serde(rename = "DIDTransferred")]
+    DidTransferred { did_id: String, previous_owner: String, new_owner: String },
+    #[serde(rename = "DIDTransferCancelled")]
+    DidTransferCancelled { did_id: String },
+    ControllersChanged { did_id: String, controllers: Vec<String>, thresholds: Thresholds },
+    OperationProposed { did_id: String, op_id: u64, action: DidAction },
+    OperationApproved { did_id: String, op_id: u64 },
+    OperationExecuted { did_id: String, op_id: u64 },
+    CredentialIssued {
+        did_id: String,
+        key: String,
+        value: String,
+        #[serde(default)]
+        not_before: Option<u64>,
+        #[serde(default)]
+        expires_at: Option<u64>,
+        /// Registered issuer DID for credentials issued by a third party.
+        #[serde(default)]
+        issuer: Option<String>,
+        #[serde(default)]
+        schema: Option<SchemaRef>,
+    },
+    SchemaRegistered { schema_id: String, version: u32, schema: serde_json::Value },
+    SchemaDeprecated { schema_id: String, version: u32 },
+    /// Several changes made by one actor that took effect together.
+    Batch { events: Vec<VaultEventKind> },
+    CredentialRevoked {
+        did_id: String,
+        key: String,
+        #[serde(default)]
+        reason: String,
+    },
+    CredentialHidden { did_id: String, key: String, hidden: bool },
+    /// Removal of a credential by its holder, or of one left behind by a DID
+    /// that no longer exists.
+    CredentialPurged { did_id: String, key: String },
+    #[serde(rename = "DIDFrozen")]
+    DidFrozen { did_id: String, reason: String },
+    #[serde(rename = "DIDUnfrozen")]
+    DidUnfrozen { did_id: String },
+    RoleGranted { party: String, role: Role },
+    RoleRevoked { party: String, role: Role },
+}
+
+impl VaultEventKind {
+    /// The DID the event is about; `None` for vault-wide events.
+    pub fn did_id(&self) -> Option<&str> {
+        let did_id = match self {
+            VaultEventKind::DidCreated { did_id, .. }
+            | VaultEventKind::DidUpdated { did_id, .. }
+            | VaultEventKind::DidRevoked { did_id, .. }
+            | VaultEventKind::DidTransferProposed { did_id, .. }
+            | VaultEventKind::DidTransferred { did_id, .. }
+            | VaultEventKind::DidTransferCancelled { did_id }
+            | VaultEventKind::ControllersChanged { did_id, .. }
+            | VaultEventKind::OperationProposed { did_id, .. }
+            | VaultEventKind::OperationApproved { did_id, .. }
+            | VaultEventKind::OperationExecuted { did_id, .. }
+            | VaultEventKind::CredentialIssued { did_id, .. }
+            | VaultEventKind::CredentialRevoked { did_id, .. }
+            | VaultEventKind::CredentialHidden { did_id, .. }
+            | VaultEventKind::CredentialPurged { did_id, .. }
+            | VaultEventKind::DidFrozen { did_id, .. }
+            | VaultEventKind::DidUnfrozen { did_id } => did_id,
+            VaultEventKind::RoleGranted { .. }
+            | VaultEventKind::RoleRevoked { .. }
+            | VaultEventKind::SchemaRegistered { .. }
+            | VaultEventKind::SchemaDeprecated { .. }
+            | VaultEventKind::Batch { .. } => return None,
+        };
+        Some(did_id)
+    }
+
+    /// Every DID the event touches, including those inside a batch.
+    pub fn did_ids(&self) -> Vec<&str> {
+        match self {
+            VaultEventKind::Batch { events } => events.iter().filter_map(|e| e.did_id()).collect(),
+            _ => self.did_id().into_iter().collect(),
+        }
+    }
+}
+
+/// The capacity in which the actor of an event acted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Authority {
+    /// The DID's owner or controllers, or whoever creates or takes over a DID.
+    #[default]
+    Owner,
+    /// A vault admin overriding or acting outside the owner's control.
+    Admin,
+    /// A controller of a registered issuer DID acting on a credential it
+    /// issued to another DID.
+    Issuer,
+}
+
+/// One entry of the vault journal. `seq` starts at 1 and increases by one
+/// per successful mutation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultEvent {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub actor: String,
+    #[serde(default)]
+    pub authority: Authority,
+    #[serde(flatten)]
+    pub kind: VaultEventKind,
+}
+
+/// Selects a page of journal entries, optionally restricted to one DID.
+#[derive(Debug, Clone)]
+pub struct EventQuery {
+    pub did_id: Option<String>,
+    /// Only events with a `seq` greater than this are returned.
+    pub after_seq: u64,
+    pub limit: usize,
+}
+
+impl EventQuery {
+    pub fn all(limit: usize) -> EventQuery {
+        EventQuery { did_id: None, after_seq: 0, limit }
+    }
+
+    pub fn for_did(did_id: &str, limit: usize) -> EventQuery {
+        EventQuery { did_id: Some(did_id.to_string()), after_seq: 0, limit }
+    }
+
+    pub fn after(mut self, seq: u64) -> EventQuery {
+        self.after_seq = seq;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventPage {
+    pub events: Vec<VaultEvent>,
+    /// Pass as `after_seq` to fetch the next page; `None` on the last page.
+    pub next_cursor: Option<u64>,
+}
+
+/// A `DIDVault.sol` log as delivered by the chain indexer: the raw topics
+/// and data from `eth_getLogs`, plus what the indexer knows about the
+/// transaction that emitted it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLog {
+    pub block_number: u64,
+    pub log_index: u64,
+    pub block_timestamp: u64,
+    /// `msg.sender` of the emitting transaction.
+    pub sender: String,
+    /// `Admin` for logs emitted by `onlyAdmin` functions.
+    #[serde(default)]
+    pub authority: Authority,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+impl VaultEvent {
+    /// Orders chain logs by block and log index, decodes them and numbers
+    /// them as journal entries following `after_seq`.
+    pub fn from_chain_logs(mut logs: Vec<ChainLog>, after_seq: u64) -> Result<Vec<VaultEvent>, VaultError> {
+        logs.sort_by_key(|l| (l.block_number, l.log_index));
+        logs.into_iter()
+            .zip(after_seq + 1..)
+            .map(|(log, seq)| {
+                let kind = decode_log(log.topics, log.data.to_vec()).map_err(|reason| VaultError::InvalidChainLog {
+                    block_number: log.block_number,
+                    log_index: log.log_index,
+                    reason,
+                })?;
+                Ok(VaultEvent { seq, timestamp: log.block_timestamp, actor: log.sender, authority: log.authority, kind })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::abi::{encode, Token};
+    use ethers::contract::EthEvent;
+    use ethers::types::Address;
+    use ethers::utils::keccak256;
+
+    use super::*;
+    use crate::relayer::{
+        CredentialIssuedFilter, CredentialRevokedFilter, DidcreatedFilter, DidfrozenFilter, DidrevokedFilter,
+        DidtransferProposedFilter, DidtransferredFilter, DidunfrozenFilter, DidupdatedFilter,
+    };
+    use crate::{BatchOp, DIDVault, MemoryStore, MockClock, NewCredential, Thresholds, VaultStore};
+
+    const ADMIN: &str = "0x52908400098527886E0F7030069857D2E4169EE7";
+    const ALICE: &str = "0x8617E340B3D01FA5F11F306F4090FD50E238070D";
+    const BOB: &str = "0xde709f2102306220921060314715629080e2fb77";
+
+    fn state(vault: &DIDVault) -> String {
+        vault.snapshot().unwrap().to_json().unwrap()
+    }
+
+    fn journal(vault: &DIDVault, after_seq: u64) -> Vec<VaultEvent> {
+        vault.history(&EventQuery::all(usize::MAX).after(after_seq)).unwrap().events
+    }
+
+    /// A vault that went through most kinds of event, plus the journal
+    /// position halfway through.
+    fn live() -> (DIDVault, u64) {
+        let clock = MockClock::new(1_000);
+        let mut vault = DIDVault::new(ADMIN).with_clock(clock.clone());
+        vault.create_did("did:dv:a", ALICE, "m").unwrap();
+        vault.create_did("did:dv:b", BOB, "").unwrap();
+        vault.issue_credential_with("did:dv:a", ALICE, NewCredential::new("k", "v").expires_at(5_000)).unwrap();
+        vault.register_schema(ADMIN, "age", serde_json::json!({ "type": "string" })).unwrap();
+        vault.register_issuer(ADMIN, "did:dv:b").unwrap();
+        vault.issue_credential_as_issuer("did:dv:b", BOB, "did:dv:a", NewCredential::new("deg", "BSc")).unwrap();
+        clock.advance(10);
+        let half = vault.store.last_event_seq().unwrap();
+
+        vault.set_controllers("did:dv:a", ALICE, &[ALICE, BOB, "carol"], Thresholds { update: 2, ..Thresholds::default() }).unwrap();
+        let op_id = vault.propose_operation("did:dv:a", BOB, DidAction::Update { metadata: "m2".to_string() }).unwrap();
+        vault.approve_operation("did:dv:a", op_id, ALICE).unwrap();
+        vault.freeze_did("did:dv:b", ADMIN, "audit").unwrap();
+        vault.unfreeze_did("did:dv:b", ADMIN).unwrap();
+        vault.apply_batch(BOB, vec![
+            BatchOp::CreateDid { did_id: "did:dv:c".to_string(), metadata: String::new() },
+            BatchOp::Did { did_id: "did:dv:c".to_string(), action: DidAction::Revoke { reason: "test".to_string() } },
+        ]).unwrap();
+        clock.advance(10);
+        vault.transfer_did("did:dv:a", ALICE, BOB).unwrap();
+        vault.accept_transfer("did:dv:a", BOB).unwrap();
+        vault.revoke_did("did:dv:b", BOB, "done").unwrap();
+        (vault, half)
+    }
+
+    #[test]
+    fn journal_replay_matches_live_vault() {
+        let (live, _) = live();
+        let replayed = DIDVault::replay(ADMIN, MemoryStore::new(), journal(&live, 0)).unwrap();
+        assert_eq!(state(&replayed), state(&live));
+    }
+
+    #[test]
+    fn snapshot_and_tail_match_live_vault() {
+        let (live, half) = live();
+        let snapshot = DIDVault::replay(ADMIN, MemoryStore::new(), journal(&live, 0).into_iter().take(half as usize))
+            .unwrap()
+            .snapshot()
+            .unwrap();
+        let restored = DIDVault::restore(MemoryStore::new(), snapshot, journal(&live, half)).unwrap();
+        assert_eq!(state(&restored), state(&live));
+    }
+
+    fn address(a: &str) -> Address {
+        a.parse().unwrap()
+    }
+
+    /// A log emitted by `sender` in block `n`, with the hashed DID id as the
+    /// first topic after the signature.
+    fn log(n: u64, sender: &str, signature: H256, did_id: &str, topics: &[&str], data: Vec<Token>) -> ChainLog {
+        let mut all = vec![signature, H256(keccak256(did_id))];
+        all.extend(topics.iter().map(|a| H256::from(address(a))));
+        let mut data = data;
+        data.insert(0, Token::String(did_id.to_string()));
+        ChainLog {
+            block_number: n,
+            log_index: 0,
+            block_timestamp: 1_000 + n,
+            sender: sender.to_string(),
+            authority: Authority::Owner,
+            topics: all,
+            data: encode(&data).into(),
+        }
+    }
+
+    fn text(s: &str) -> Token {
+        Token::String(s.to_string())
+    }
+
+    #[test]
+    fn decoded_chain_logs_match_live_vault() {
+        let clock = MockClock::new(1_000);
+        let mut live = DIDVault::new(ADMIN).with_clock(clock.clone());
+        let d = "did:dv:a";
+        let mut logs = Vec::new();
+
+        clock.set(1_001);
+        live.create_did(d, ALICE, "m").unwrap();
+        logs.push(log(1, ALICE, DidcreatedFilter::signature(), d, &[ALICE], vec![text("m")]));
+        clock.set(1_002);
+        live.update_did(d, ALICE, "m2").unwrap();
+        logs.push(log(2, ALICE, DidupdatedFilter::signature(), d, &[], vec![text("m2")]));
+        clock.set(1_003);
+        live.issue_credential(d, ALICE, "k", "v").unwrap();
+        logs.push(log(3, ALICE, CredentialIssuedFilter::signature(), d, &[], vec![text("k"), text("v")]));
+        clock.set(1_004);
+        live.revoke_credential(d, ALICE, "k").unwrap();
+        logs.push(log(4, ALICE, CredentialRevokedFilter::signature(), d, &[], vec![text("k")]));
+        clock.set(1_005);
+        live.freeze_did(d, ADMIN, "audit").unwrap();
+        let mut frozen = log(5, ADMIN, DidfrozenFilter::signature(), d, &[], vec![text("audit")]);
+        frozen.authority = Authority::Admin;
+        logs.push(frozen);
+        clock.set(1_006);
+        live.unfreeze_did(d, ADMIN).unwrap();
+        let mut unfrozen = log(6, ADMIN, DidunfrozenFilter::signature(), d, &[], vec![]);
+        unfrozen.authority = Authority::Admin;
+        logs.push(unfrozen);
+        clock.set(1_007);
+        live.transfer_did(d, ALICE, BOB).unwrap();
+        logs.push(log(7, ALICE, DidtransferProposedFilter::signature(), d, &[ALICE, BOB], vec![]));
+        clock.set(1_008);
+        live.accept_transfer(d, BOB).unwrap();
+        logs.push(log(8, BOB, DidtransferredFilter::signature(), d, &[ALICE, BOB], vec![]));
+        clock.set(1_009);
+        live.revoke_did(d, BOB, "done").unwrap();
+        logs.push(log(9, BOB, DidrevokedFilter::signature(), d, &[], vec![text("done")]));
+
+        logs.reverse();
+        let events = VaultEvent::from_chain_logs(logs, 0).unwrap();
+        assert_eq!(events, journal(&live, 0));
+        let replayed = DIDVault::replay(ADMIN, MemoryStore::new(), events).unwrap();
+        assert_eq!(state(&replayed), state(&live));
+    }
+
+    #[test]
+    fn logs_with_a_mismatched_id_topic_are_rejected() {
+        let mut forged = log(1, ALICE, DidcreatedFilter::signature(), "did:dv:a", &[ALICE], vec![text("")]);
+        forged.topics[1] = H256(keccak256("did:dv:b"));
+        let err = VaultEvent::from_chain_logs(vec![forged], 0).unwrap_err();
+        assert!(matches!(err, VaultError::InvalidChainLog { block_number: 1, .. }));
+    }
+}
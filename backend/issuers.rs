@@ -0,0 +1,137 @@
+use crate::admin::Role;
+use crate::controllers::Operation;
+use crate::events::Authority;
+use crate::{DIDVault, Limit, NewCredential, VaultError, VaultEventKind, VaultStore};
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Allows the controllers of `issuer_did` to issue credentials onto other
+    /// DIDs. The registry is kept as `Issuer` role grants to DID ids.
+    pub fn register_issuer(&mut self, caller: &str, issuer_did: &str) -> Result<(), VaultError> {
+        self.resolve_did(issuer_did)?;
+        self.grant_role(caller, issuer_did, Role::Issuer)
+    }
+
+    /// Stops `issuer_did` from issuing. It keeps the right to revoke what it
+    /// has already issued.
+    pub fn deregister_issuer(&mut self, caller: &str, issuer_did: &str) -> Result<(), VaultError> {
+        self.revoke_role(caller, issuer_did, Role::Issuer)
+    }
+
+    pub fn is_registered_issuer(&self, issuer_did: &str) -> Result<bool, VaultError> {
+        self.has_role(issuer_did, Role::Issuer)
+    }
+
+    /// Issues a credential about `subject_did` on behalf of the registered
+    /// issuer DID `issuer_did`, which `caller` controls.
+    pub fn issue_credential_as_issuer(
+        &mut self,
+        issuer_did: &str,
+        caller: &str,
+        subject_did: &str,
+        cred: NewCredential,
+    ) -> Result<(), VaultError> {
+        self.authorize(issuer_did, caller, Operation::Issue)?;
+        if !self.is_registered_issuer(issuer_did)? {
+            return Err(VaultError::IssuerNotRegistered(issuer_did.to_string()));
+        }
+        let subject = self.resolve_did(subject_did)?;
+        if subject.frozen {
+            return Err(VaultError::DidFrozen(subject_did.to_string()));
+        }
+        self.check_issue(&subject.id, &cred, Some(issuer_did))?;
+        let kind = VaultEventKind::CredentialIssued {
+            did_id: subject.id,
+            key: cred.key,
+            value: cred.value,
+            not_before: cred.not_before,
+            expires_at: cred.expires_at,
+            issuer: Some(issuer_did.to_string()),
+            schema: cred.schema,
+        };
+        self.commit_as(caller, Authority::Issuer, kind)
+    }
+
+    /// Revokes a credential that `issuer_did` issued onto `subject_did`.
+    pub fn revoke_issued_credential(
+        &mut self,
+        issuer_did: &str,
+        caller: &str,
+        subject_did: &str,
+        key: &str,
+        reason: &str,
+    ) -> Result<(), VaultError> {
+        self.authorize(issuer_did, caller, Operation::Issue)?;
+        let cred = self.store.get_credential(subject_did, key)?.filter(|c| c.is_active())
+            .ok_or_else(|| VaultError::CredentialNotFound { did_id: subject_did.to_string(), key: key.to_string() })?;
+        if cred.issuer.as_deref() != Some(issuer_did) {
+            let issuer = cred.issuer.unwrap_or_else(|| subject_did.to_string());
+            return Err(VaultError::NotIssuer { did_id: subject_did.to_string(), key: key.to_string(), issuer });
+        }
+        self.check_size(Limit::ReasonBytes, reason.len())?;
+        let kind = VaultEventKind::CredentialRevoked {
+            did_id: subject_did.to_string(),
+            key: key.to_string(),
+            reason: reason.to_string(),
+        };
+        self.commit_as(caller, Authority::Issuer, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::vault_with;
+    use crate::Validity;
+
+    fn vault() -> DIDVault {
+        let mut vault = vault_with("a", ["did:dv:alice"], &[]);
+        vault.create_did("did:dv:uni", "u", "").unwrap();
+        vault.register_issuer("admin", "did:dv:uni").unwrap();
+        vault.issue_credential_as_issuer("did:dv:uni", "u", "did:dv:alice", NewCredential::new("degree", "BSc")).unwrap();
+        vault
+    }
+
+    #[test]
+    fn holder_cannot_overwrite_revoked_issued_credential() {
+        let mut vault = vault();
+        vault.revoke_issued_credential("did:dv:uni", "u", "did:dv:alice", "degree", "fraud").unwrap();
+        let err = vault.issue_credential("did:dv:alice", "a", "degree", "PhD").unwrap_err();
+        assert!(matches!(err, VaultError::NotIssuer { issuer, .. } if issuer == "did:dv:uni"));
+        let (cred, validity) = vault.get_credential("did:dv:alice", "degree").unwrap();
+        assert_eq!((cred.value.as_str(), validity), ("BSc", Validity::Revoked));
+    }
+
+    #[test]
+    fn issuer_cannot_overwrite_holder_credential() {
+        let mut vault = vault();
+        vault.create_did("did:dv:bob", "b", "").unwrap();
+        vault.issue_credential("did:dv:bob", "b", "degree", "BA").unwrap();
+        vault.revoke_credential("did:dv:bob", "b", "degree").unwrap();
+        let err = vault
+            .issue_credential_as_issuer("did:dv:uni", "u", "did:dv:bob", NewCredential::new("degree", "BSc"))
+            .unwrap_err();
+        assert!(matches!(err, VaultError::NotIssuer { issuer, .. } if issuer == "did:dv:bob"));
+    }
+
+    #[test]
+    fn issuer_can_reissue_its_revoked_credential() {
+        let mut vault = vault();
+        vault.revoke_issued_credential("did:dv:uni", "u", "did:dv:alice", "degree", "typo").unwrap();
+        vault.issue_credential_as_issuer("did:dv:uni", "u", "did:dv:alice", NewCredential::new("degree", "BSc (Hons)")).unwrap();
+        let (cred, validity) = vault.get_credential("did:dv:alice", "degree").unwrap();
+        assert_eq!((cred.value.as_str(), validity), ("BSc (Hons)", Validity::Valid));
+    }
+
+    #[test]
+    fn holder_delete_keeps_issued_revocation() {
+        let mut vault = vault();
+        vault.revoke_issued_credential("did:dv:uni", "u", "did:dv:alice", "degree", "fraud").unwrap();
+        vault.delete_credential("did:dv:alice", "a", "degree").unwrap();
+        assert!(vault.credentials("did:dv:alice").unwrap().is_empty());
+        let (cred, validity) = vault.get_credential("did:dv:alice", "degree").unwrap();
+        assert_eq!((cred.hidden, validity), (true, Validity::Revoked));
+
+        let err = vault.issue_credential("did:dv:alice", "a", "degree", "PhD").unwrap_err();
+        assert!(matches!(err, VaultError::NotIssuer { issuer, .. } if issuer == "did:dv:uni"));
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+use base58::{FromBase58, ToBase58};
+use serde::{Deserialize, Serialize};
+
+/// Algorithms of the public keys the vault understands, with their
+/// multicodec codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyType {
+    Ed25519,
+    /// Key agreement only; cannot sign.
+    X25519,
+    Secp256k1,
+    P256,
+}
+
+impl KeyType {
+    pub const ALL: [KeyType; 4] = [KeyType::Ed25519, KeyType::X25519, KeyType::Secp256k1, KeyType::P256];
+
+    /// Code from the multicodec table, e.g. `0xed` for `ed25519-pub`.
+    pub fn multicodec(self) -> u64 {
+        match self {
+            KeyType::Ed25519 => 0xed,
+            KeyType::X25519 => 0xec,
+            KeyType::Secp256k1 => 0xe7,
+            KeyType::P256 => 0x1200,
+        }
+    }
+
+    /// Length of the raw key; elliptic-curve points are compressed.
+    pub fn key_len(self) -> usize {
+        match self {
+            KeyType::Ed25519 | KeyType::X25519 => 32,
+            KeyType::Secp256k1 | KeyType::P256 => 33,
+        }
+    }
+
+    pub fn can_sign(self) -> bool {
+        self != KeyType::X25519
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// Multibase other than base58btc (`z`), or not valid base58.
+    InvalidMultibase,
+    UnknownCodec(u64),
+    InvalidLength { key_type: KeyType, len: usize },
+    /// The bytes are not a point on the key type's curve.
+    InvalidPoint(KeyType),
+    /// The key type cannot sign.
+    CannotSign(KeyType),
+    InvalidSignature,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyError::InvalidMultibase => write!(f, "not a base58btc multibase string"),
+            KeyError::UnknownCodec(code) => write!(f, "unsupported multicodec 0x{:x}", code),
+            KeyError::InvalidLength { key_type, len } => {
+                write!(f, "{:?} key is {} bytes, expected {}", key_type, len, key_type.key_len())
+            }
+            KeyError::InvalidPoint(key_type) => write!(f, "not a valid {:?} public key", key_type),
+            KeyError::CannotSign(key_type) => write!(f, "{:?} keys cannot sign", key_type),
+            KeyError::InvalidSignature => write!(f, "signature does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// A public key tagged with its algorithm. Serialized as its multibase
+/// form, as in a `publicKeyMultibase` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PublicKey {
+    pub key_type: KeyType,
+    pub bytes: Vec<u8>,
+}
+
+impl PublicKey {
+    /// Checks that `bytes` is a valid key of `key_type`. Every 32 bytes
+    /// are an X25519 key; the other types must decode to a curve point.
+    pub fn new(key_type: KeyType, bytes: &[u8]) -> Result<PublicKey, KeyError> {
+        if bytes.len() != key_type.key_len() {
+            return Err(KeyError::InvalidLength { key_type, len: bytes.len() });
+        }
+        let valid = match key_type {
+            KeyType::Ed25519 => ed25519_key(bytes).is_some(),
+            KeyType::X25519 => true,
+            KeyType::Secp256k1 => k256::PublicKey::from_sec1_bytes(bytes).is_ok(),
+            KeyType::P256 => p256::PublicKey::from_sec1_bytes(bytes).is_ok(),
+        };
+        if !valid {
+            return Err(KeyError::InvalidPoint(key_type));
+        }
+        Ok(PublicKey { key_type, bytes: bytes.to_vec() })
+    }
+
+    /// The X25519 form of an Ed25519 key, for key agreement with the
+    /// holder of the signing key.
+    pub fn to_x25519(&self) -> Option<PublicKey> {
+        if self.key_type != KeyType::Ed25519 {
+            return None;
+        }
+        let montgomery = ed25519_key(&self.bytes)?.to_montgomery();
+        Some(PublicKey { key_type: KeyType::X25519, bytes: montgomery.to_bytes().to_vec() })
+    }
+
+    /// Checks `signature` over `message`: 64 bytes of Ed25519, or of ECDSA
+    /// `r || s` over the SHA-256 of `message` for secp256k1 and P-256.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), KeyError> {
+        use k256::ecdsa::signature::Verifier;
+
+        let verified = match self.key_type {
+            KeyType::Ed25519 => {
+                let signature = ed25519_dalek::Signature::from_slice(signature).map_err(|_| KeyError::InvalidSignature)?;
+                ed25519_key(&self.bytes).is_some_and(|key| key.verify_strict(message, &signature).is_ok())
+            }
+            KeyType::X25519 => return Err(KeyError::CannotSign(self.key_type)),
+            KeyType::Secp256k1 => {
+                let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&self.bytes).map_err(|_| KeyError::InvalidPoint(self.key_type))?;
+                let signature = k256::ecdsa::Signature::from_slice(signature).map_err(|_| KeyError::InvalidSignature)?;
+                key.verify(message, &signature).is_ok()
+            }
+            KeyType::P256 => {
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&self.bytes).map_err(|_| KeyError::InvalidPoint(self.key_type))?;
+                let signature = p256::ecdsa::Signature::from_slice(signature).map_err(|_| KeyError::InvalidSignature)?;
+                key.verify(message, &signature).is_ok()
+            }
+        };
+        if !verified {
+            return Err(KeyError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// The key prefixed with the varint of its multicodec code.
+    pub fn to_multicodec(&self) -> Vec<u8> {
+        let mut out = varint(self.key_type.multicodec());
+        out.extend_from_slice(&self.bytes);
+        out
+    }
+
+    pub fn from_multicodec(bytes: &[u8]) -> Result<PublicKey, KeyError> {
+        let (code, len) = read_varint(bytes).ok_or(KeyError::UnknownCodec(0))?;
+        let key_type = KeyType::ALL
+            .into_iter()
+            .find(|t| t.multicodec() == code)
+            .ok_or(KeyError::UnknownCodec(code))?;
+        PublicKey::new(key_type, &bytes[len..])
+    }
+
+    /// `z` followed by the base58btc multicodec key.
+    pub fn to_multibase(&self) -> String {
+        format!("z{}", self.to_multicodec().to_base58())
+    }
+
+    pub fn from_multibase(s: &str) -> Result<PublicKey, KeyError> {
+        let encoded = s.strip_prefix('z').ok_or(KeyError::InvalidMultibase)?;
+        let bytes = encoded.from_base58().map_err(|_| KeyError::InvalidMultibase)?;
+        PublicKey::from_multicodec(&bytes)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_multibase())
+    }
+}
+
+impl From<PublicKey> for String {
+    fn from(key: PublicKey) -> String {
+        key.to_multibase()
+    }
+}
+
+impl TryFrom<String> for PublicKey {
+    type Error = KeyError;
+
+    fn try_from(s: String) -> Result<PublicKey, KeyError> {
+        PublicKey::from_multibase(&s)
+    }
+}
+
+fn ed25519_key(bytes: &[u8]) -> Option<ed25519_dalek::VerifyingKey> {
+    ed25519_dalek::VerifyingKey::from_bytes(bytes.try_into().ok()?).ok()
+}
+
+/// Unsigned LEB128, as multicodec and multihash prefixes are written.
+pub(crate) fn varint(mut n: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Reads a varint from the front of `bytes`, returning it and its length.
+pub(crate) fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut n = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        n |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((n, i + 1));
+        }
+    }
+    None
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{DIDVault, VaultError, VaultStore};
+
+/// A bounded resource. Sizes are in bytes of UTF-8, as `bytes(s).length`
+/// counts them in `DIDVault.sol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Limit {
+    MetadataBytes,
+    KeyBytes,
+    ValueBytes,
+    /// Credentials held by one DID that have not been revoked.
+    CredentialsPerDid,
+    /// Active DIDs owned by one party.
+    DidsPerOwner,
+    /// Reasons given for revoking, freezing or deactivating.
+    ReasonBytes,
+}
+
+impl Limit {
+    /// The `require` message `DIDVault.sol` reverts with when over the limit.
+    pub fn revert_reason(self) -> &'static str {
+        match self {
+            Limit::MetadataBytes => "Metadata too large",
+            Limit::KeyBytes => "Key too long",
+            Limit::ValueBytes => "Value too large",
+            Limit::CredentialsPerDid => "Too many credentials",
+            Limit::DidsPerOwner => "Too many DIDs",
+            Limit::ReasonBytes => "Reason too long",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Limits {
+    pub max_metadata_bytes: usize,
+    pub max_key_bytes: usize,
+    pub max_value_bytes: usize,
+    pub max_credentials_per_did: usize,
+    pub max_dids_per_owner: usize,
+    pub max_reason_bytes: usize,
+}
+
+/// The `MAX_*` constants of `DIDVault.sol`. Raising any of them lets the
+/// vault accept operations the contract reverts.
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_metadata_bytes: 1024,
+            max_key_bytes: 64,
+            max_value_bytes: 1024,
+            max_credentials_per_did: 100,
+            max_dids_per_owner: 100,
+            max_reason_bytes: 256,
+        }
+    }
+}
+
+impl Limits {
+    pub fn get(&self, limit: Limit) -> usize {
+        match limit {
+            Limit::MetadataBytes => self.max_metadata_bytes,
+            Limit::KeyBytes => self.max_key_bytes,
+            Limit::ValueBytes => self.max_value_bytes,
+            Limit::CredentialsPerDid => self.max_credentials_per_did,
+            Limit::DidsPerOwner => self.max_dids_per_owner,
+            Limit::ReasonBytes => self.max_reason_bytes,
+        }
+    }
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    pub fn with_limits(mut self, limits: Limits) -> DIDVault<S> {
+        self.limits = limits;
+        self
+    }
+
+    /// Fails with `LimitExceeded` if `size` is over `limit`.
+    pub(crate) fn check_size(&self, limit: Limit, size: usize) -> Result<(), VaultError> {
+        let max = self.limits.get(limit);
+        if size > max {
+            return Err(VaultError::LimitExceeded { limit, max });
+        }
+        Ok(())
+    }
+
+    /// Checks that `owner` may take on one more DID.
+    pub(crate) fn check_did_quota(&self, owner: &str) -> Result<(), VaultError> {
+        self.check_size(Limit::DidsPerOwner, self.store.owned_dids(owner)?.len() + 1)
+    }
+
+    /// Checks that `did_id` may hold a credential under `key`, replacing an
+    /// expired one there if any.
+    pub(crate) fn check_credential_quota(&self, did_id: &str, key: &str) -> Result<(), VaultError> {
+        let held = self.store.get_credentials(did_id)?.unwrap_or_default()
+            .values()
+            .filter(|c| c.is_active() && c.key != key)
+            .count();
+        self.check_size(Limit::CredentialsPerDid, held + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault() -> DIDVault {
+        DIDVault::new("admin").with_limits(Limits { max_dids_per_owner: 2, max_reason_bytes: 8, ..Limits::default() })
+    }
+
+    #[test]
+    fn reasons_are_bounded() {
+        let mut vault = vault();
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        let too_long = VaultError::LimitExceeded { limit: Limit::ReasonBytes, max: 8 };
+        assert_eq!(vault.revoke_did("did:dv:a", "o", "lost my key").unwrap_err(), too_long);
+        assert_eq!(vault.freeze_did("did:dv:a", "admin", "court order").unwrap_err(), too_long);
+        assert_eq!(vault.force_deactivate_did("did:dv:a", "admin", "court order").unwrap_err(), too_long);
+        vault.revoke_did("did:dv:a", "o", "lost").unwrap();
+    }
+
+    #[test]
+    fn quota_counts_active_dids_of_the_current_owner() {
+        let mut vault = vault();
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        vault.create_did("did:dv:b", "o", "").unwrap();
+        let full = VaultError::LimitExceeded { limit: Limit::DidsPerOwner, max: 2 };
+        assert_eq!(vault.create_did("did:dv:c", "o", "").unwrap_err(), full);
+
+        vault.revoke_did("did:dv:a", "o", "").unwrap();
+        vault.create_did("did:dv:c", "o", "").unwrap();
+        vault.transfer_did("did:dv:b", "o", "p").unwrap();
+        vault.accept_transfer("did:dv:b", "p").unwrap();
+        assert_eq!(vault.store.owned_dids("o").unwrap(), ["did:dv:c"]);
+        assert_eq!(vault.store.owned_dids("p").unwrap(), ["did:dv:b"]);
+        vault.create_did("did:dv:d", "o", "").unwrap();
+    }
+}
@@ -1,2425 +1,992 @@
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
 // DIDVault Rust Backend
 // ~~~~~~~~~~~~~~~~~~~~~
 // This Rust project manages Decentralized Identities (DIDs)
 // with secure storage, verification, and CRUD operations.
 
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
+mod admin;
+mod batch;
+mod clock;
+mod consistency;
+mod controllers;
+mod did;
+mod did_key;
+mod document;
+mod error;
+mod events;
+mod issuers;
+mod keys;
+mod limits;
+mod queries;
+mod relayer;
+mod resolver;
+mod schemas;
+mod shared;
+mod snapshot;
+mod store;
+#[cfg(test)]
+mod testing;
+mod validity;
+mod versions;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use controllers::check_controllers;
+use queries::check_page_limit;
+
+pub use admin::Role;
+pub use batch::BatchOp;
+pub use clock::{Clock, MockClock, SystemClock};
+pub use consistency::{Orphan, OrphanKind};
+pub use controllers::{DidAction, Operation, PendingOperation, Thresholds};
+pub use did::{generate_key_did, generate_simple_did, genesis_message, is_key_did, verify_key_did, Did, DidComponent, DidParseError, DidUrl, VAULT_METHOD};
+pub use did_key::{did_key_public_key, generate_did_key, resolve_did_key, KEY_METHOD};
+pub use document::{DidDocument, Representation, Service, ServiceEndpoint, VerificationMethod, VerificationRelationship};
+pub use error::VaultError;
+pub use events::{Authority, ChainLog, EventPage, EventQuery, VaultEvent, VaultEventKind};
+pub use keys::{KeyError, KeyType, PublicKey};
+pub use limits::{Limit, Limits};
+pub use queries::{CredentialPage, CredentialQuery, DidPage, DidQuery};
+pub use relayer::{sync_batch, sync_transfer, DIDVaultContract, Relayer, SimpleRelayer};
+pub use resolver::{
+    DidResolver, DocumentMetadata, KeyResolver, ResolutionError, ResolutionMetadata, ResolutionOptions, ResolutionResult,
+    UniversalResolver, VaultResolver,
+};
+pub use schemas::{CredentialSchema, SchemaRef};
+pub use shared::SharedVault;
+pub use snapshot::{VaultSnapshot, SNAPSHOT_VERSION};
+pub use store::{MemoryStore, SledStore, VaultStore};
+pub use validity::Validity;
+pub use versions::VersionQuery;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DidStatus {
+    #[default]
+    Active,
+    Deactivated,
+}
+
+/// A DID record. Revoked DIDs stay in the vault as deactivated tombstones so
+/// their ids can never be claimed again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DID {
     pub id: String,
     pub owner: String,
     pub metadata: String,
     pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
+    /// Starts at 1 and grows with every change to metadata, controllers,
+    /// ownership or status. Each version is kept for `get_did_at`.
+    #[serde(default)]
+    pub version_id: u64,
+    #[serde(default)]
+    pub updated_at: u64,
+    /// Grows with every change to this record, including ones that do not
+    /// make a new version. Compared by the `_if_revision` methods.
+    #[serde(default)]
+    pub revision: u64,
+    #[serde(default)]
+    pub status: DidStatus,
+    #[serde(default)]
+    pub revoked_at: Option<u64>,
+    #[serde(default)]
+    pub revoked_by: Option<String>,
+    #[serde(default)]
+    pub revocation_reason: Option<String>,
+    /// Set while an ownership transfer waits for the new owner to accept.
+    #[serde(default)]
+    pub pending_owner: Option<String>,
+    /// Parties allowed to act on the DID; always includes `owner`.
+    #[serde(default)]
+    pub controllers: Vec<String>,
+    #[serde(default)]
+    pub thresholds: Thresholds,
+    #[serde(default)]
+    pub pending_operations: Vec<PendingOperation>,
+    /// Set by an admin; blocks all owner and controller actions.
+    #[serde(default)]
+    pub frozen: bool,
+    /// Key the id was derived from, for DIDs made by `create_did_from_key`.
+    #[serde(default)]
+    pub genesis_key: Option<PublicKey>,
+    /// Set once the DID is transferred or its controllers change, after
+    /// which the genesis key only proves the id, not control.
+    #[serde(default)]
+    pub genesis_key_retired: bool,
+}
+
+impl DID {
+    pub fn is_active(&self) -> bool {
+        self.status == DidStatus::Active
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CredentialStatus {
+    #[default]
+    Active,
+    Revoked,
+}
+
+/// A credential held by a DID. Revoked credentials are kept with their
+/// revocation details so lookups can report them as revoked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Credential {
     pub key: String,
     pub value: String,
     pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
+    /// Unix timestamp before which the credential is not yet valid.
+    #[serde(default)]
+    pub not_before: Option<u64>,
+    /// Unix timestamp from which the credential is expired.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Registered issuer DID that issued the credential; `None` when the
+    /// DID's own controllers issued it.
+    #[serde(default)]
+    pub issuer: Option<String>,
+    /// Schema the value was validated against at issuance.
+    #[serde(default)]
+    pub schema: Option<SchemaRef>,
+    /// Set by the holder to leave the credential out of its own listing.
+    /// Hidden credentials still resolve for verifiers.
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub status: CredentialStatus,
+    #[serde(default)]
+    pub revoked_at: Option<u64>,
+    #[serde(default)]
+    pub revocation_reason: Option<String>,
+}
+
+impl Credential {
+    pub fn is_active(&self) -> bool {
+        self.status == CredentialStatus::Active
+    }
+}
+
+/// A credential to be issued, built with `NewCredential::new` and the
+/// optional settings below.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewCredential {
     pub key: String,
     pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
+    #[serde(default)]
+    pub not_before: Option<u64>,
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub schema: Option<SchemaRef>,
 }
 
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
+impl NewCredential {
+    pub fn new(key: &str, value: &str) -> NewCredential {
+        NewCredential { key: key.to_string(), value: value.to_string(), not_before: None, expires_at: None, schema: None }
     }
 
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
+    pub fn not_before(mut self, at: u64) -> NewCredential {
+        self.not_before = Some(at);
+        self
     }
 
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
+    pub fn expires_at(mut self, at: u64) -> NewCredential {
+        self.expires_at = Some(at);
+        self
     }
 
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
+    /// Validates the value against `schema` on issuance.
+    pub fn schema(mut self, schema: SchemaRef) -> NewCredential {
+        self.schema = Some(schema);
+        self
     }
 }
 
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
+pub struct DIDVault<S: VaultStore = MemoryStore> {
+    pub store: S,
     pub admin: String,
+    pub clock: Arc<dyn Clock>,
+    pub limits: Limits,
 }
 
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
+impl DIDVault<MemoryStore> {
+    pub fn new(admin: &str) -> DIDVault<MemoryStore> {
+        DIDVault::with_store(admin, MemoryStore::new())
     }
 }
 
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
+impl<S: VaultStore> DIDVault<S> {
+    pub fn with_store(admin: &str, store: S) -> DIDVault<S> {
         DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
+            store,
             admin: admin.to_string(),
+            clock: Arc::new(SystemClock),
+            limits: Limits::default(),
         }
     }
 
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
+    /// Replaces the system clock, e.g. with a `MockClock` in tests.
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> DIDVault<S> {
+        self.clock = Arc::new(clock);
+        self
     }
 
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
+    pub fn now(&self) -> u64 {
+        self.clock.now()
     }
 
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
+    /// Rebuilds a vault in an empty `store` from an ordered event stream,
+    /// such as an exported journal or `VaultEvent::from_chain_logs`.
+    pub fn replay<I>(admin: &str, store: S, events: I) -> Result<DIDVault<S>, VaultError>
+    where
+        I: IntoIterator<Item = VaultEvent>,
+    {
+        let mut vault = DIDVault::with_store(admin, store);
+        vault.check_empty()?;
+        vault.replay_events(events)?;
+        Ok(vault)
     }
 
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
+    /// Rebuilds a vault in an empty `store` from a snapshot plus the events
+    /// recorded after it.
+    pub fn restore<I>(store: S, snapshot: VaultSnapshot, tail: I) -> Result<DIDVault<S>, VaultError>
+    where
+        I: IntoIterator<Item = VaultEvent>,
+    {
+        snapshot.validate()?;
+        let mut vault = DIDVault::with_store(&snapshot.admin, store);
+        vault.check_empty()?;
+        for did in &snapshot.dids {
+            vault.store.put_did(did)?;
         }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
-    }
-
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
+        for (did_id, creds) in &snapshot.credentials {
+            for cred in creds.values() {
+                vault.store.put_credential(did_id, cred)?;
+            }
         }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
-    }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
-    }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
-
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
+        for (party, roles) in &snapshot.roles {
+            vault.store.put_roles(party, roles)?;
         }
-    }
-
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
+        for did in snapshot.did_versions.values().flatten() {
+            vault.store.put_did_version(did)?;
         }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
+        for schema in &snapshot.schemas {
+            vault.store.put_schema(schema)?;
         }
-        did.metadata = metadata.to_string();
+        vault.store.set_journal_base(snapshot.last_seq)?;
+        vault.replay_events(tail)?;
+        Ok(vault)
     }
 
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
+    /// Captures the current state for `restore`.
+    pub fn snapshot(&self) -> Result<VaultSnapshot, VaultError> {
+        let mut dids = self.store.list_dids()?;
+        dids.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut snapshot = VaultSnapshot {
+            version: SNAPSHOT_VERSION,
+            admin: self.admin.clone(),
+            last_seq: self.store.last_event_seq()?,
+            dids,
+            credentials: BTreeMap::new(),
+            roles: self.store.list_roles()?.into_iter().collect(),
+            did_versions: BTreeMap::new(),
+            schemas: self.store.list_schemas()?,
+        };
+        snapshot.schemas.sort_by(|a, b| (&a.id, a.version).cmp(&(&b.id, b.version)));
+        for did in &snapshot.dids {
+            snapshot.did_versions.insert(did.id.clone(), self.store.list_did_versions(&did.id)?);
         }
-        self.dids.remove(did_id);
-    }
-
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
+        for (did_id, cred) in self.store.list_credentials()? {
+            snapshot.credentials.entry(did_id).or_default().insert(cred.key.clone(), cred);
         }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
+        Ok(snapshot)
     }
 
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
+    /// Exports the vault as a pretty-printed JSON snapshot.
+    pub fn export_json(&self) -> Result<String, VaultError> {
+        self.snapshot()?.to_json()
     }
 
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
+    /// Exports the vault as a compact CBOR snapshot.
+    pub fn export_cbor(&self) -> Result<Vec<u8>, VaultError> {
+        self.snapshot()?.to_cbor()
     }
-}
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
-
-// DIDVault Rust Backend
-// ~~~~~~~~~~~~~~~~~~~~~
-// This Rust project manages Decentralized Identities (DIDs)
-// with secure storage, verification, and CRUD operations.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Clone)]
-pub struct DID {
-    pub id: String,
-    pub owner: String,
-    pub metadata: String,
-    pub created_at: u64,
-    pub exists: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct Credential {
-    pub key: String,
-    pub value: String,
-    pub issued_at: u64,
-    pub exists: bool,
-}
-
-pub struct DIDVault {
-    pub dids: HashMap<String, DID>,
-    pub credentials: HashMap<String, HashMap<String, Credential>>,
-    pub admin: String,
-}
+    /// Imports a JSON snapshot into an empty `store`.
+    pub fn import_json(store: S, json: &str) -> Result<DIDVault<S>, VaultError> {
+        DIDVault::restore(store, VaultSnapshot::from_json(json)?, Vec::new())
+    }
 
-impl DIDVault {
-    pub fn new(admin: &str) -> DIDVault {
-        DIDVault {
-            dids: HashMap::new(),
-            credentials: HashMap::new(),
-            admin: admin.to_string(),
-        }
+    /// Imports a CBOR snapshot into an empty `store`.
+    pub fn import_cbor(store: S, bytes: &[u8]) -> Result<DIDVault<S>, VaultError> {
+        DIDVault::restore(store, VaultSnapshot::from_cbor(bytes)?, Vec::new())
     }
 
-    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        if self.dids.contains_key(did_id) {
-            panic!("DID already exists");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        self.dids.insert(did_id.to_string(), DID {
-            id: did_id.to_string(),
+    pub fn create_did(&mut self, did_id: &str, owner: &str, metadata: &str) -> Result<(), VaultError> {
+        self.check_create(did_id, owner, metadata, None)?;
+        self.commit(owner, VaultEventKind::DidCreated {
+            did_id: did_id.to_string(),
             owner: owner.to_string(),
             metadata: metadata.to_string(),
-            created_at: now,
-            exists: true,
-        });
-    }
-
-    pub fn update_did(&mut self, did_id: &str, owner: &str, metadata: &str) {
-        let did = self.dids.get_mut(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can update DID");
-        }
-        did.metadata = metadata.to_string();
-    }
-
-    pub fn revoke_did(&mut self, did_id: &str, owner: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke DID");
-        }
-        self.dids.remove(did_id);
+            genesis_key: None,
+        })
+    }
+
+    /// Creates a self-certifying DID derived from `key` and returns its id.
+    /// `signature` is by `key` over `genesis_message(did_id, owner)`, so
+    /// only the key holder can choose who owns the DID. The key is
+    /// published in the DID document as `#genesis-key`.
+    pub fn create_did_from_key(
+        &mut self,
+        owner: &str,
+        key: &PublicKey,
+        signature: &[u8],
+        metadata: &str,
+    ) -> Result<String, VaultError> {
+        if !key.key_type.can_sign() {
+            return Err(VaultError::UnsupportedKeyType(key.key_type));
+        }
+        let did_id = generate_key_did(key);
+        key.verify(&genesis_message(&did_id, owner), signature)
+            .map_err(|reason| VaultError::InvalidSignature { did_id: did_id.clone(), reason })?;
+        self.check_create(&did_id, owner, metadata, Some(key))?;
+        self.commit(owner, VaultEventKind::DidCreated {
+            did_id: did_id.clone(),
+            owner: owner.to_string(),
+            metadata: metadata.to_string(),
+            genesis_key: Some(key.clone()),
+        })?;
+        Ok(did_id)
     }
 
-    pub fn issue_credential(&mut self, did_id: &str, owner: &str, key: &str, value: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can issue credential");
-        }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let cred = Credential { key: key.to_string(), value: value.to_string(), issued_at: now, exists: true };
-        self.credentials.entry(did_id.to_string()).or_insert_with(HashMap::new).insert(key.to_string(), cred);
+    pub fn update_did(&mut self, did_id: &str, caller: &str, metadata: &str) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Update)?;
+        self.run(did_id, caller, DidAction::Update { metadata: metadata.to_string() })
     }
 
-    pub fn revoke_credential(&mut self, did_id: &str, owner: &str, key: &str) {
-        let did = self.dids.get(did_id).expect("DID not found");
-        if did.owner != owner {
-            panic!("Only owner can revoke credential");
-        }
-        let creds = self.credentials.get_mut(did_id).expect("No credentials found");
-        creds.remove(key);
+    /// Like `update_did`, but fails with `RevisionConflict` unless the DID
+    /// is still at `expected_revision`.
+    pub fn update_did_if_revision(
+        &mut self,
+        did_id: &str,
+        caller: &str,
+        metadata: &str,
+        expected_revision: u64,
+    ) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Update)?;
+        self.check_revision(did_id, expected_revision)?;
+        self.run(did_id, caller, DidAction::Update { metadata: metadata.to_string() })
     }
-
-    pub fn get_credential(&self, did_id: &str, key: &str) -> Option<&Credential> {
-        self.credentials.get(did_id).and_then(|c| c.get(key))
+
+    /// Deactivates a DID. The record is kept as a tombstone, so the id stays
+    /// resolvable as deactivated and cannot be created again. All of its
+    /// active credentials are revoked along with it.
+    pub fn revoke_did(&mut self, did_id: &str, caller: &str, reason: &str) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Revoke)?;
+        self.run(did_id, caller, DidAction::Revoke { reason: reason.to_string() })
+    }
+
+    /// Like `revoke_did`, but fails with `RevisionConflict` unless the DID
+    /// is still at `expected_revision`.
+    pub fn revoke_did_if_revision(
+        &mut self,
+        did_id: &str,
+        caller: &str,
+        reason: &str,
+        expected_revision: u64,
+    ) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Revoke)?;
+        self.check_revision(did_id, expected_revision)?;
+        self.run(did_id, caller, DidAction::Revoke { reason: reason.to_string() })
+    }
+
+    /// Proposes handing `did_id` over to `new_owner`. Ownership changes only
+    /// once `new_owner` calls `accept_transfer`; a new proposal replaces any
+    /// pending one. Under the default transfer threshold of 1 only the owner
+    /// may call this; a higher threshold goes through `propose_operation`.
+    pub fn transfer_did(&mut self, did_id: &str, current_owner: &str, new_owner: &str) -> Result<(), VaultError> {
+        self.authorize(did_id, current_owner, Operation::Transfer)?;
+        self.run(did_id, current_owner, DidAction::Transfer { new_owner: new_owner.to_string() })
+    }
+
+    pub fn accept_transfer(&mut self, did_id: &str, new_owner: &str) -> Result<(), VaultError> {
+        let did = self.resolve_did(did_id)?;
+        if did.frozen {
+            return Err(VaultError::DidFrozen(did_id.to_string()));
+        }
+        let pending = did.pending_owner.as_deref().ok_or_else(|| VaultError::NoPendingTransfer(did_id.to_string()))?;
+        if pending != new_owner {
+            return Err(VaultError::NotPendingOwner { did_id: did_id.to_string(), caller: new_owner.to_string() });
+        }
+        check_controllers(new_owner, &did.controllers_after_transfer(new_owner), &did.thresholds)
+            .map_err(|reason| VaultError::InvalidControllers { did_id: did_id.to_string(), reason })?;
+        self.check_did_quota(new_owner)?;
+        self.commit(new_owner, VaultEventKind::DidTransferred {
+            did_id: did_id.to_string(),
+            previous_owner: did.owner,
+            new_owner: new_owner.to_string(),
+        })
+    }
+
+    pub fn cancel_transfer(&mut self, did_id: &str, caller: &str) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Transfer)?;
+        self.run(did_id, caller, DidAction::CancelTransfer)
+    }
+
+    pub fn issue_credential(&mut self, did_id: &str, caller: &str, key: &str, value: &str) -> Result<(), VaultError> {
+        self.issue_credential_with(did_id, caller, NewCredential::new(key, value))
+    }
+
+    /// Issues a credential with an optional validity window and schema. The
+    /// credential is valid from `not_before` (inclusive) until `expires_at`
+    /// (exclusive); either bound may be left open.
+    pub fn issue_credential_with(&mut self, did_id: &str, caller: &str, cred: NewCredential) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Issue)?;
+        self.run(did_id, caller, DidAction::IssueCredential(cred))
+    }
+
+    pub fn revoke_credential(&mut self, did_id: &str, caller: &str, key: &str) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Issue)?;
+        self.run(did_id, caller, DidAction::RevokeCredential { key: key.to_string() })
+    }
+
+    pub fn hide_credential(&mut self, did_id: &str, caller: &str, key: &str, hidden: bool) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Issue)?;
+        self.run(did_id, caller, DidAction::HideCredential { key: key.to_string(), hidden })
+    }
+
+    /// Removes a credential from the holder's DID. A credential from a
+    /// registered issuer is only hidden, so its revocation record stays and
+    /// its key stays bound to the issuer.
+    pub fn delete_credential(&mut self, did_id: &str, caller: &str, key: &str) -> Result<(), VaultError> {
+        self.authorize(did_id, caller, Operation::Issue)?;
+        self.run(did_id, caller, DidAction::DeleteCredential { key: key.to_string() })
+    }
+
+    /// Credentials the DID has not hidden, sorted by key.
+    pub fn credentials(&self, did_id: &str) -> Result<Vec<Credential>, VaultError> {
+        let mut creds: Vec<_> = self.store.get_credentials(did_id)?.unwrap_or_default()
+            .into_values()
+            .filter(|c| !c.hidden)
+            .collect();
+        creds.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(creds)
+    }
+
+    /// Reads a page of the journal, oldest first.
+    pub fn history(&self, query: &EventQuery) -> Result<EventPage, VaultError> {
+        check_page_limit(query.limit)?;
+        let mut events: Vec<VaultEvent> = Vec::new();
+        let mut cursor = query.after_seq;
+        loop {
+            let batch = self.store.events_after(cursor, query.limit.saturating_add(1))?;
+            let Some(last) = batch.last() else {
+                return Ok(EventPage { events, next_cursor: None });
+            };
+            cursor = last.seq;
+            for event in batch {
+                if !query.did_id.as_deref().is_none_or(|id| event.kind.did_ids().contains(&id)) {
+                    continue;
+                }
+                // A match past a full page means another page follows.
+                if events.len() == query.limit {
+                    let next_cursor = events.last().map(|e| e.seq);
+                    return Ok(EventPage { events, next_cursor });
+                }
+                events.push(event);
+            }
+        }
+    }
+
+    /// Returns the DID record, including deactivated tombstones.
+    pub fn get_did(&self, did_id: &str) -> Result<Option<DID>, VaultError> {
+        self.store.get_did(did_id)
+    }
+
+    /// Returns an active DID, or `DidDeactivated` for a tombstone.
+    pub fn resolve_did(&self, did_id: &str) -> Result<DID, VaultError> {
+        let did = self.load_did(did_id)?;
+        if !did.is_active() {
+            return Err(VaultError::DidDeactivated(did_id.to_string()));
+        }
+        Ok(did)
+    }
+
+    fn check_revision(&self, did_id: &str, expected: u64) -> Result<(), VaultError> {
+        let actual = self.load_did(did_id)?.revision;
+        if actual != expected {
+            return Err(VaultError::RevisionConflict { did_id: did_id.to_string(), expected, actual });
+        }
+        Ok(())
+    }
+
+    /// Checks that `owner` can create `did_id` with `metadata`.
+    /// Checks that `did_id` can be created for `owner`. Ids of the
+    /// self-certifying form need the `genesis_key` they were derived from.
+    fn check_create(
+        &self,
+        did_id: &str,
+        owner: &str,
+        metadata: &str,
+        genesis_key: Option<&PublicKey>,
+    ) -> Result<(), VaultError> {
+        let did = Did::parse(did_id)
+            .map_err(|reason| VaultError::InvalidDid { did_id: did_id.to_string(), reason })?;
+        if did.method != VAULT_METHOD {
+            return Err(VaultError::UnsupportedDidMethod { did_id: did_id.to_string(), method: did.method });
+        }
+        if genesis_key.is_none() && is_key_did(did_id) {
+            return Err(VaultError::GenesisKeyRequired(did_id.to_string()));
+        }
+        match self.store.get_did(did_id)? {
+            Some(did) if !did.is_active() => return Err(VaultError::DidDeactivated(did_id.to_string())),
+            Some(_) => return Err(VaultError::DidAlreadyExists(did_id.to_string())),
+            None => {}
+        }
+        self.check_size(Limit::MetadataBytes, metadata.len())?;
+        self.check_did_quota(owner)
+    }
+
+    fn load_did(&self, did_id: &str) -> Result<DID, VaultError> {
+        self.store.get_did(did_id)?.ok_or_else(|| VaultError::DidNotFound(did_id.to_string()))
+    }
+
+    /// Checks that `issuer`, or the holder itself if `None`, can issue `cred`
+    /// onto `did_id`. A credential may only be replaced by its own issuer,
+    /// so a revoked credential cannot be overwritten to hide the revocation.
+    fn check_issue(&self, did_id: &str, cred: &NewCredential, issuer: Option<&str>) -> Result<(), VaultError> {
+        let key = &cred.key;
+        self.check_size(Limit::KeyBytes, key.len())?;
+        self.check_size(Limit::ValueBytes, cred.value.len())?;
+        self.check_credential_quota(did_id, key)?;
+        if let Some(current) = self.store.get_credential(did_id, key)? {
+            if current.issuer.as_deref() != issuer {
+                let issuer = current.issuer.unwrap_or_else(|| did_id.to_string());
+                return Err(VaultError::NotIssuer { did_id: did_id.to_string(), key: key.clone(), issuer });
+            }
+            // Expired credentials may be renewed under the same key.
+            if matches!(current.validity_at(self.now()), Validity::Valid | Validity::NotYetValid) {
+                return Err(VaultError::CredentialAlreadyExists { did_id: did_id.to_string(), key: key.clone() });
+            }
+        }
+        if let (Some(not_before), Some(expires_at)) = (cred.not_before, cred.expires_at) {
+            if expires_at <= not_before {
+                return Err(VaultError::InvalidValidity { did_id: did_id.to_string(), key: key.clone() });
+            }
+        }
+        if let Some(schema) = &cred.schema {
+            self.check_schema(did_id, cred, schema)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that `action` can be applied to `did` in its current state.
+    fn check_action(&self, did: &DID, action: &DidAction) -> Result<(), VaultError> {
+        let did_id = &did.id;
+        match action {
+            DidAction::IssueCredential(cred) => self.check_issue(did_id, cred, None)?,
+            DidAction::RevokeCredential { key } => {
+                if self.store.get_credentials(did_id)?.is_none() {
+                    return Err(VaultError::NoCredentialsForDid(did_id.clone()));
+                }
+                let cred = self.store.get_credential(did_id, key)?.filter(|c| c.is_active())
+                    .ok_or_else(|| VaultError::CredentialNotFound { did_id: did_id.clone(), key: key.clone() })?;
+                if let Some(issuer) = cred.issuer {
+                    return Err(VaultError::NotIssuer { did_id: did_id.clone(), key: key.clone(), issuer });
+                }
+            }
+            DidAction::HideCredential { key, .. } | DidAction::DeleteCredential { key } => {
+                if self.store.get_credential(did_id, key)?.is_none() {
+                    return Err(VaultError::CredentialNotFound { did_id: did_id.clone(), key: key.clone() });
+                }
+            }
+            DidAction::CancelTransfer => {
+                if did.pending_owner.is_none() {
+                    return Err(VaultError::NoPendingTransfer(did_id.clone()));
+                }
+            }
+            DidAction::SetControllers { controllers, thresholds } => {
+                check_controllers(&did.owner, controllers, thresholds)
+                    .map_err(|reason| VaultError::InvalidControllers { did_id: did_id.clone(), reason })?;
+            }
+            DidAction::Update { metadata } => self.check_size(Limit::MetadataBytes, metadata.len())?,
+            // The previous owner stops being a controller, so every
+            // threshold must still be reachable without them.
+            DidAction::Transfer { new_owner } => {
+                check_controllers(new_owner, &did.controllers_after_transfer(new_owner), &did.thresholds)
+                    .map_err(|reason| VaultError::InvalidControllers { did_id: did_id.clone(), reason })?;
+            }
+            DidAction::Revoke { reason } => self.check_size(Limit::ReasonBytes, reason.len())?,
+        }
+        Ok(())
+    }
+
+    /// Validates and commits an action whose authorization has been checked.
+    fn run(&mut self, did_id: &str, actor: &str, action: DidAction) -> Result<(), VaultError> {
+        let kind = self.action_event(did_id, action)?;
+        self.commit(actor, kind)
+    }
+
+    /// Validates an action and returns the event recording it.
+    fn action_event(&self, did_id: &str, action: DidAction) -> Result<VaultEventKind, VaultError> {
+        let did = self.resolve_did(did_id)?;
+        self.check_action(&did, &action)?;
+        let did_id = did.id;
+        let kind = match action {
+            DidAction::Update { metadata } => VaultEventKind::DidUpdated { did_id, metadata },
+            DidAction::Revoke { reason } => VaultEventKind::DidRevoked { did_id, reason },
+            DidAction::IssueCredential(cred) => VaultEventKind::CredentialIssued {
+                did_id,
+                key: cred.key,
+                value: cred.value,
+                not_before: cred.not_before,
+                expires_at: cred.expires_at,
+                issuer: None,
+                schema: cred.schema,
+            },
+            DidAction::HideCredential { key, hidden } => VaultEventKind::CredentialHidden { did_id, key, hidden },
+            DidAction::DeleteCredential { key } => {
+                if self.store.get_credential(&did_id, &key)?.is_some_and(|c| c.issuer.is_some()) {
+                    VaultEventKind::CredentialHidden { did_id, key, hidden: true }
+                } else {
+                    VaultEventKind::CredentialPurged { did_id, key }
+                }
+            }
+            DidAction::RevokeCredential { key } => {
+                VaultEventKind::CredentialRevoked { did_id, key, reason: String::new() }
+            }
+            DidAction::Transfer { new_owner } => VaultEventKind::DidTransferProposed { did_id, new_owner },
+            DidAction::CancelTransfer => VaultEventKind::DidTransferCancelled { did_id },
+            DidAction::SetControllers { controllers, thresholds } => {
+                VaultEventKind::ControllersChanged { did_id, controllers, thresholds }
+            }
+        };
+        Ok(kind)
+    }
+
+    fn check_empty(&self) -> Result<(), VaultError> {
+        if self.store.last_event_seq()? != 0 || !self.store.list_dids()?.is_empty() {
+            return Err(VaultError::StoreNotEmpty);
+        }
+        Ok(())
+    }
+
+    fn replay_events<I>(&mut self, events: I) -> Result<(), VaultError>
+    where
+        I: IntoIterator<Item = VaultEvent>,
+    {
+        for event in events {
+            let expected = self.store.last_event_seq()? + 1;
+            if event.seq != expected {
+                return Err(VaultError::EventOutOfOrder { expected, found: event.seq });
+            }
+            self.atomically(|vault| {
+                vault.apply(&event)?;
+                vault.store.append_event(&event)
+            })?;
+        }
+        self.store.flush()
+    }
+
+    /// Applies an already validated mutation and appends it to the journal.
+    fn commit(&mut self, actor: &str, kind: VaultEventKind) -> Result<(), VaultError> {
+        self.commit_as(actor, Authority::Owner, kind)
+    }
+
+    fn commit_as(&mut self, actor: &str, authority: Authority, kind: VaultEventKind) -> Result<(), VaultError> {
+        let event = VaultEvent {
+            seq: self.store.last_event_seq()? + 1,
+            timestamp: self.now(),
+            actor: actor.to_string(),
+            authority,
+            kind,
+        };
+        self.atomically(|vault| {
+            vault.apply(&event)?;
+            vault.store.append_event(&event)
+        })?;
+        self.store.flush()
+    }
+
+    /// Runs `f` as one group of store writes, so that an operation's state
+    /// changes and its journal entry are stored together or not at all.
+    fn atomically<T, F>(&mut self, f: F) -> Result<T, VaultError>
+    where
+        F: FnOnce(&mut DIDVault<S>) -> Result<T, VaultError>,
+    {
+        self.store.begin_writes()?;
+        match f(self) {
+            Ok(value) => {
+                self.store.commit_writes()?;
+                Ok(value)
+            }
+            Err(e) => {
+                self.store.discard_writes()?;
+                Err(e)
+            }
+        }
+    }
+
+    fn apply(&mut self, event: &VaultEvent) -> Result<(), VaultError> {
+        match &event.kind {
+            VaultEventKind::DidCreated { did_id, owner, metadata, genesis_key } => {
+                if self.store.get_did(did_id)?.is_some() {
+                    return Err(VaultError::DidAlreadyExists(did_id.clone()));
+                }
+                let did = DID {
+                    id: did_id.clone(),
+                    owner: owner.clone(),
+                    metadata: metadata.clone(),
+                    created_at: event.timestamp,
+                    version_id: 0,
+                    updated_at: event.timestamp,
+                    revision: 0,
+                    status: DidStatus::Active,
+                    revoked_at: None,
+                    revoked_by: None,
+                    revocation_reason: None,
+                    pending_owner: None,
+                    controllers: vec![owner.clone()],
+                    thresholds: Thresholds::default(),
+                    pending_operations: Vec::new(),
+                    frozen: false,
+                    genesis_key: genesis_key.clone(),
+                    genesis_key_retired: false,
+                };
+                self.put_version(did, event.timestamp)
+            }
+            VaultEventKind::DidUpdated { did_id, metadata } => {
+                let mut did = self.load_did(did_id)?;
+                did.metadata = metadata.clone();
+                self.put_version(did, event.timestamp)
+            }
+            VaultEventKind::DidRevoked { did_id, reason } => {
+                let mut did = self.load_did(did_id)?;
+                did.status = DidStatus::Deactivated;
+                did.revoked_at = Some(event.timestamp);
+                did.revoked_by = Some(event.actor.clone());
+                did.revocation_reason = Some(reason.clone());
+                did.pending_owner = None;
+                did.pending_operations.clear();
+                self.put_version(did, event.timestamp)?;
+                let cascade = format!("DID {} deactivated", did_id);
+                for mut cred in self.store.get_credentials(did_id)?.unwrap_or_default().into_values() {
+                    if cred.is_active() {
+                        revoke(&mut cred, event.timestamp, &cascade);
+                        self.store.put_credential(did_id, &cred)?;
+                    }
+                }
+                Ok(())
+            }
+            VaultEventKind::DidTransferProposed { did_id, new_owner } => {
+                let mut did = self.load_did(did_id)?;
+                did.pending_owner = Some(new_owner.clone());
+                self.save_did(did)
+            }
+            VaultEventKind::DidTransferred { did_id, new_owner, .. } => {
+                let mut did = self.load_did(did_id)?;
+                did.controllers = did.controllers_after_transfer(new_owner);
+                did.owner = new_owner.clone();
+                did.pending_owner = None;
+                did.genesis_key_retired = true;
+                self.put_version(did, event.timestamp)
+            }
+            VaultEventKind::DidTransferCancelled { did_id } => {
+                let mut did = self.load_did(did_id)?;
+                did.pending_owner = None;
+                self.save_did(did)
+            }
+            VaultEventKind::ControllersChanged { did_id, controllers, thresholds } => {
+                let mut did = self.load_did(did_id)?;
+                did.controllers = controllers.clone();
+                did.thresholds = *thresholds;
+                did.genesis_key_retired = true;
+                self.put_version(did, event.timestamp)
+            }
+            VaultEventKind::OperationProposed { did_id, op_id, action } => {
+                let mut did = self.load_did(did_id)?;
+                did.pending_operations.push(PendingOperation {
+                    id: *op_id,
+                    action: action.clone(),
+                    proposed_by: event.actor.clone(),
+                    approvals: vec![event.actor.clone()],
+                });
+                self.save_did(did)
+            }
+            VaultEventKind::OperationApproved { did_id, op_id } => {
+                let mut did = self.load_did(did_id)?;
+                let op = did.pending_operations.iter_mut().find(|op| op.id == *op_id)
+                    .ok_or_else(|| VaultError::OperationNotFound { did_id: did_id.clone(), op_id: *op_id })?;
+                op.approvals.push(event.actor.clone());
+                self.save_did(did)
+            }
+            VaultEventKind::OperationExecuted { did_id, op_id } => {
+                let mut did = self.load_did(did_id)?;
+                did.pending_operations.retain(|op| op.id != *op_id);
+                self.save_did(did)
+            }
+            VaultEventKind::CredentialIssued { did_id, key, value, not_before, expires_at, issuer, schema } => {
+                let cred = Credential {
+                    key: key.clone(),
+                    value: value.clone(),
+                    issued_at: event.timestamp,
+                    not_before: *not_before,
+                    expires_at: *expires_at,
+                    issuer: issuer.clone(),
+                    schema: schema.clone(),
+                    hidden: false,
+                    status: CredentialStatus::Active,
+                    revoked_at: None,
+                    revocation_reason: None,
+                };
+                self.store.put_credential(did_id, &cred)
+            }
+            VaultEventKind::CredentialRevoked { did_id, key, reason } => {
+                let mut cred = self.store.get_credential(did_id, key)?
+                    .ok_or_else(|| VaultError::CredentialNotFound { did_id: did_id.clone(), key: key.clone() })?;
+                revoke(&mut cred, event.timestamp, reason);
+                self.store.put_credential(did_id, &cred)
+            }
+            VaultEventKind::CredentialHidden { did_id, key, hidden } => {
+                let mut cred = self.store.get_credential(did_id, key)?
+                    .ok_or_else(|| VaultError::CredentialNotFound { did_id: did_id.clone(), key: key.clone() })?;
+                cred.hidden = *hidden;
+                self.store.put_credential(did_id, &cred)
+            }
+            VaultEventKind::CredentialPurged { did_id, key } => {
+                self.store.remove_credential(did_id, key)?;
+                Ok(())
+            }
+            VaultEventKind::DidFrozen { did_id, .. } | VaultEventKind::DidUnfrozen { did_id } => {
+                let mut did = self.load_did(did_id)?;
+                did.frozen = matches!(event.kind, VaultEventKind::DidFrozen { .. });
+                self.save_did(did)
+            }
+            VaultEventKind::RoleGranted { party, role } => {
+                let mut roles = self.store.get_roles(party)?;
+                if !roles.contains(role) {
+                    roles.push(*role);
+                }
+                self.store.put_roles(party, &roles)
+            }
+            VaultEventKind::RoleRevoked { party, role } => {
+                let mut roles = self.store.get_roles(party)?;
+                roles.retain(|r| r != role);
+                self.store.put_roles(party, &roles)
+            }
+            VaultEventKind::SchemaRegistered { schema_id, version, schema } => {
+                self.store.put_schema(&CredentialSchema {
+                    id: schema_id.clone(),
+                    version: *version,
+                    schema: schema.clone(),
+                    registered_by: event.actor.clone(),
+                    registered_at: event.timestamp,
+                    deprecated: false,
+                })
+            }
+            VaultEventKind::SchemaDeprecated { schema_id, version } => {
+                let mut schema = self.store.get_schema(schema_id, *version)?
+                    .ok_or_else(|| VaultError::SchemaNotFound(SchemaRef::new(schema_id, *version).to_string()))?;
+                schema.deprecated = true;
+                self.store.put_schema(&schema)
+            }
+            VaultEventKind::Batch { events } => {
+                for kind in events {
+                    self.apply(&VaultEvent {
+                        seq: event.seq,
+                        timestamp: event.timestamp,
+                        actor: event.actor.clone(),
+                        authority: event.authority,
+                        kind: kind.clone(),
+                    })?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Stores a changed DID record under its next revision.
+    fn save_did(&mut self, mut did: DID) -> Result<(), VaultError> {
+        did.revision += 1;
+        self.store.put_did(&did)
+    }
+
+    /// Stores `did` as its next version.
+    fn put_version(&mut self, mut did: DID, at: u64) -> Result<(), VaultError> {
+        did.version_id += 1;
+        did.updated_at = at;
+        did.revision += 1;
+        self.store.put_did(&did)?;
+        self.store.put_did_version(&did)
+    }
+}
+
+fn revoke(cred: &mut Credential, at: u64, reason: &str) {
+    cred.status = CredentialStatus::Revoked;
+    cred.revoked_at = Some(at);
+    cred.revocation_reason = if reason.is_empty() { None } else { Some(reason.to_string()) };
+}
+
+/// Opens the sled-backed vault at `DID_VAULT_DB` and reports what it holds.
+fn main() -> Result<(), VaultError> {
+    let path = std::env::var("DID_VAULT_DB").unwrap_or_else(|_| "did-vault.db".to_string());
+    let admin = std::env::var("DID_VAULT_ADMIN").unwrap_or_else(|_| "admin".to_string());
+    let vault = DIDVault::with_store(&admin, SledStore::open(&path)?);
+    println!(
+        "{}: {} DIDs, {} journal entries",
+        path,
+        vault.store.list_dids()?.len(),
+        vault.store.last_event_seq()?
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revoked_ids_cannot_be_created_again() {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        vault.revoke_did("did:dv:a", "o", "lost key").unwrap();
+
+        let gone = VaultError::DidDeactivated("did:dv:a".to_string());
+        assert_eq!(vault.create_did("did:dv:a", "mallory", "").unwrap_err(), gone);
+        let ops = vec![BatchOp::CreateDid { did_id: "did:dv:a".to_string(), metadata: String::new() }];
+        assert_eq!(vault.apply_batch("mallory", ops).unwrap_err(), gone);
+        let did = vault.get_did("did:dv:a").unwrap().unwrap();
+        assert_eq!((did.owner.as_str(), did.is_active()), ("o", false));
+    }
+
+    #[test]
+    fn stale_revisions_conflict() {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "o", "v1").unwrap();
+        let seen = vault.resolve_did("did:dv:a").unwrap().revision;
+        vault.update_did_if_revision("did:dv:a", "o", "v2", seen).unwrap();
+
+        let err = vault.update_did_if_revision("did:dv:a", "o", "v3", seen).unwrap_err();
+        assert_eq!(err, VaultError::RevisionConflict { did_id: "did:dv:a".to_string(), expected: seen, actual: seen + 1 });
+        let err = vault.revoke_did_if_revision("did:dv:a", "o", "", seen).unwrap_err();
+        assert!(matches!(err, VaultError::RevisionConflict { .. }));
+        let did = vault.resolve_did("did:dv:a").unwrap();
+        assert_eq!((did.metadata.as_str(), did.revision), ("v2", seen + 1));
+
+        vault.revoke_did_if_revision("did:dv:a", "o", "", seen + 1).unwrap();
+        assert!(!vault.get_did("did:dv:a").unwrap().unwrap().is_active());
+    }
+
+    #[test]
+    fn revision_grows_without_a_new_version() {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        let before = vault.resolve_did("did:dv:a").unwrap();
+        vault.transfer_did("did:dv:a", "o", "p").unwrap();
+        let after = vault.resolve_did("did:dv:a").unwrap();
+        assert_eq!((after.version_id, after.revision), (before.version_id, before.revision + 1));
+        let err = vault.update_did_if_revision("did:dv:a", "o", "m", before.revision).unwrap_err();
+        assert!(matches!(err, VaultError::RevisionConflict { .. }));
     }
 }
-
-// ~ Additional utility functions, repeated structures, modules, comments
-// ~ This code is repeated and modularized to reach 1000-1500 lines
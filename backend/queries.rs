@@ -0,0 +1,236 @@
+use crate::{Credential, DIDVault, Validity, VaultError, VaultStore, DID};
+
+/// Selects a page of one DID's credentials, ordered by key.
+#[derive(Debug, Clone, Default)]
+pub struct CredentialQuery {
+    pub key_prefix: Option<String>,
+    /// Only credentials issued by this registered issuer DID.
+    pub issuer: Option<String>,
+    /// Validity as of the time of the query.
+    pub status: Option<Validity>,
+    /// Inclusive bounds on `issued_at`.
+    pub issued_from: Option<u64>,
+    pub issued_until: Option<u64>,
+    pub include_hidden: bool,
+    /// Only credentials with a key greater than this are returned.
+    pub after_key: Option<String>,
+    pub limit: usize,
+}
+
+impl CredentialQuery {
+    pub fn new(limit: usize) -> CredentialQuery {
+        CredentialQuery { limit, ..CredentialQuery::default() }
+    }
+
+    pub fn key_prefix(mut self, prefix: &str) -> CredentialQuery {
+        self.key_prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn issuer(mut self, issuer_did: &str) -> CredentialQuery {
+        self.issuer = Some(issuer_did.to_string());
+        self
+    }
+
+    pub fn status(mut self, status: Validity) -> CredentialQuery {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn issued_between(mut self, from: u64, until: u64) -> CredentialQuery {
+        self.issued_from = Some(from);
+        self.issued_until = Some(until);
+        self
+    }
+
+    pub fn include_hidden(mut self) -> CredentialQuery {
+        self.include_hidden = true;
+        self
+    }
+
+    pub fn after(mut self, key: &str) -> CredentialQuery {
+        self.after_key = Some(key.to_string());
+        self
+    }
+
+    fn matches(&self, cred: &Credential, now: u64) -> bool {
+        self.after_key.as_ref().is_none_or(|after| cred.key > *after)
+            && self.key_prefix.as_ref().is_none_or(|prefix| cred.key.starts_with(prefix.as_str()))
+            && self.issuer.as_ref().is_none_or(|issuer| cred.issuer.as_ref() == Some(issuer))
+            && self.status.is_none_or(|status| cred.validity_at(now) == status)
+            && self.issued_from.is_none_or(|from| cred.issued_at >= from)
+            && self.issued_until.is_none_or(|until| cred.issued_at <= until)
+            && (self.include_hidden || !cred.hidden)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CredentialPage {
+    pub credentials: Vec<Credential>,
+    /// Pass to `CredentialQuery::after` to fetch the next page; `None` on
+    /// the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// Selects a page of DIDs, ordered by id.
+#[derive(Debug, Clone, Default)]
+pub struct DidQuery {
+    pub owner: Option<String>,
+    /// Inclusive bounds on `created_at`.
+    pub created_from: Option<u64>,
+    pub created_until: Option<u64>,
+    pub include_deactivated: bool,
+    /// Only DIDs with an id greater than this are returned.
+    pub after_id: Option<String>,
+    pub limit: usize,
+}
+
+impl DidQuery {
+    pub fn new(limit: usize) -> DidQuery {
+        DidQuery { limit, ..DidQuery::default() }
+    }
+
+    pub fn owner(mut self, owner: &str) -> DidQuery {
+        self.owner = Some(owner.to_string());
+        self
+    }
+
+    pub fn created_between(mut self, from: u64, until: u64) -> DidQuery {
+        self.created_from = Some(from);
+        self.created_until = Some(until);
+        self
+    }
+
+    pub fn include_deactivated(mut self) -> DidQuery {
+        self.include_deactivated = true;
+        self
+    }
+
+    pub fn after(mut self, did_id: &str) -> DidQuery {
+        self.after_id = Some(did_id.to_string());
+        self
+    }
+
+    fn matches(&self, did: &DID) -> bool {
+        self.after_id.as_ref().is_none_or(|after| did.id > *after)
+            && self.owner.as_ref().is_none_or(|owner| did.owner == *owner)
+            && self.created_from.is_none_or(|from| did.created_at >= from)
+            && self.created_until.is_none_or(|until| did.created_at <= until)
+            && (self.include_deactivated || did.is_active())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DidPage {
+    pub dids: Vec<DID>,
+    /// Pass to `DidQuery::after` to fetch the next page; `None` on the last
+    /// page.
+    pub next_cursor: Option<String>,
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Lists the credentials of a DID matching `query`. The cursor is the
+    /// last key returned, so pages stay stable while credentials are added.
+    pub fn query_credentials(&self, did_id: &str, query: &CredentialQuery) -> Result<CredentialPage, VaultError> {
+        check_page_limit(query.limit)?;
+        let now = self.now();
+        let mut credentials: Vec<_> = self.store.get_credentials(did_id)?.unwrap_or_default()
+            .into_values()
+            .filter(|c| query.matches(c, now))
+            .collect();
+        credentials.sort_by(|a, b| a.key.cmp(&b.key));
+        let next_cursor = page(&mut credentials, query.limit).map(|last| last.key.clone());
+        Ok(CredentialPage { credentials, next_cursor })
+    }
+
+    /// Lists the DIDs matching `query`, e.g. all DIDs of one wallet.
+    pub fn query_dids(&self, query: &DidQuery) -> Result<DidPage, VaultError> {
+        check_page_limit(query.limit)?;
+        let mut dids: Vec<_> = self.store.list_dids()?.into_iter().filter(|d| query.matches(d)).collect();
+        dids.sort_by(|a, b| a.id.cmp(&b.id));
+        let next_cursor = page(&mut dids, query.limit).map(|last| last.id.clone());
+        Ok(DidPage { dids, next_cursor })
+    }
+}
+
+/// Fails for a limit of 0: its page would be empty yet leave items over.
+pub(crate) fn check_page_limit(limit: usize) -> Result<(), VaultError> {
+    if limit == 0 {
+        return Err(VaultError::ZeroPageLimit);
+    }
+    Ok(())
+}
+
+/// Cuts `items` down to `limit` and returns the last kept item if more
+/// were left over.
+fn page<T>(items: &mut Vec<T>, limit: usize) -> Option<&T> {
+    if items.len() <= limit {
+        return None;
+    }
+    items.truncate(limit);
+    items.last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::vault_with;
+    use crate::EventQuery;
+
+    fn vault() -> DIDVault {
+        vault_with("o", ["did:dv:a", "did:dv:b", "did:dv:c"], &["k1", "k2"])
+    }
+
+    #[test]
+    fn zero_limit_is_rejected() {
+        let vault = vault();
+        assert_eq!(vault.history(&EventQuery::all(0)).unwrap_err(), VaultError::ZeroPageLimit);
+        assert_eq!(vault.query_dids(&DidQuery::new(0)).unwrap_err(), VaultError::ZeroPageLimit);
+        let err = vault.query_credentials("did:dv:a", &CredentialQuery::new(0)).unwrap_err();
+        assert_eq!(err, VaultError::ZeroPageLimit);
+    }
+
+    #[test]
+    fn pages_of_one_reach_the_end() {
+        let vault = vault();
+        let mut query = EventQuery::for_did("did:dv:b", 1);
+        let mut events = 0;
+        loop {
+            let page = vault.history(&query).unwrap();
+            events += page.events.len();
+            match page.next_cursor {
+                Some(cursor) => query.after_seq = cursor,
+                None => break,
+            }
+        }
+        assert_eq!(events, 3);
+
+        let mut query = DidQuery::new(1);
+        let mut dids = Vec::new();
+        loop {
+            let page = vault.query_dids(&query).unwrap();
+            dids.extend(page.dids.into_iter().map(|d| d.id));
+            match page.next_cursor {
+                Some(cursor) => query = query.after(&cursor),
+                None => break,
+            }
+        }
+        assert_eq!(dids, ["did:dv:a", "did:dv:b", "did:dv:c"]);
+    }
+
+    #[test]
+    fn last_filtered_page_has_no_cursor() {
+        let mut vault = vault();
+        let page = vault.history(&EventQuery::for_did("did:dv:b", 3)).unwrap();
+        assert_eq!(page.events.len(), 3);
+        assert_eq!(page.next_cursor, None);
+
+        vault.update_did("did:dv:b", "o", "m").unwrap();
+        let page = vault.history(&EventQuery::for_did("did:dv:b", 3)).unwrap();
+        let last = page.events.last().unwrap().seq;
+        assert_eq!(page.next_cursor, Some(last));
+        let rest = vault.history(&EventQuery::for_did("did:dv:b", 3).after(last)).unwrap();
+        assert_eq!(rest.events.len(), 1);
+        assert_eq!(rest.next_cursor, None);
+    }
+}
@@ -1,4 +1,10 @@
 use async_trait::async_trait;
+use ethers::prelude::*;
+
+use ethers::abi::RawLog;
+use ethers::utils::{keccak256, to_checksum};
+
+use crate::{Role, VaultEventKind};
 
 #[async_trait]
 pub trait Relayer {
@@ -14,3 +20,167 @@ impl Relayer for SimpleRelayer {
         Ok(())
     }
 }
+
+abigen!(
+    DIDVaultContract,
+    r#"[
+        function createDID(string didId, string metadata)
+        function updateDID(string didId, string metadata)
+        function revokeDID(string didId, string reason)
+        function transferDID(string didId, address newOwner)
+        function acceptDIDTransfer(string didId)
+        function cancelDIDTransfer(string didId)
+        function issueCredential(string didId, string key, string value)
+        function revokeCredential(string didId, string key)
+        function multicall(bytes[] calls) returns (bytes[])
+        event DIDCreated(string indexed id, address indexed owner, string didId, string metadata)
+        event DIDUpdated(string indexed id, string didId, string metadata)
+        event DIDRevoked(string indexed id, string didId, string reason)
+        event DIDTransferProposed(string indexed id, address indexed from, address indexed to, string didId)
+        event DIDTransferred(string indexed id, address indexed from, address indexed to, string didId)
+        event DIDTransferCancelled(string indexed id, string didId)
+        event CredentialIssued(string indexed id, string didId, string key, string value)
+        event CredentialRevoked(string indexed id, string didId, string key)
+        event DIDFrozen(string indexed id, string didId, string reason)
+        event DIDUnfrozen(string indexed id, string didId)
+        event RoleGranted(address indexed account, uint8 role)
+        event RoleRevoked(address indexed account, uint8 role)
+    ]"#,
+);
+
+/// Decodes a `DIDVault.sol` log from its topics and data into the vault
+/// event it records. The hashed id in the topics must match the id in the
+/// data.
+pub(crate) fn decode_log(topics: Vec<H256>, data: Vec<u8>) -> Result<VaultEventKind, String> {
+    let event = DIDVaultContractEvents::decode_log(&RawLog { topics, data }).map_err(|e| e.to_string())?;
+    let (id_hash, kind) = match event {
+        DIDVaultContractEvents::DidcreatedFilter(e) => (e.id, VaultEventKind::DidCreated {
+            did_id: e.did_id,
+            owner: to_checksum(&e.owner, None),
+            metadata: e.metadata,
+            genesis_key: None,
+        }),
+        DIDVaultContractEvents::DidupdatedFilter(e) => {
+            (e.id, VaultEventKind::DidUpdated { did_id: e.did_id, metadata: e.metadata })
+        }
+        DIDVaultContractEvents::DidrevokedFilter(e) => {
+            (e.id, VaultEventKind::DidRevoked { did_id: e.did_id, reason: e.reason })
+        }
+        DIDVaultContractEvents::DidtransferProposedFilter(e) => {
+            (e.id, VaultEventKind::DidTransferProposed { did_id: e.did_id, new_owner: to_checksum(&e.to, None) })
+        }
+        DIDVaultContractEvents::DidtransferredFilter(e) => (e.id, VaultEventKind::DidTransferred {
+            did_id: e.did_id,
+            previous_owner: to_checksum(&e.from, None),
+            new_owner: to_checksum(&e.to, None),
+        }),
+        DIDVaultContractEvents::DidtransferCancelledFilter(e) => {
+            (e.id, VaultEventKind::DidTransferCancelled { did_id: e.did_id })
+        }
+        DIDVaultContractEvents::CredentialIssuedFilter(e) => (e.id, VaultEventKind::CredentialIssued {
+            did_id: e.did_id,
+            key: e.key,
+            value: e.value,
+            not_before: None,
+            expires_at: None,
+            issuer: None,
+            schema: None,
+        }),
+        DIDVaultContractEvents::CredentialRevokedFilter(e) => {
+            (e.id, VaultEventKind::CredentialRevoked { did_id: e.did_id, key: e.key, reason: String::new() })
+        }
+        DIDVaultContractEvents::DidfrozenFilter(e) => {
+            (e.id, VaultEventKind::DidFrozen { did_id: e.did_id, reason: e.reason })
+        }
+        DIDVaultContractEvents::DidunfrozenFilter(e) => (e.id, VaultEventKind::DidUnfrozen { did_id: e.did_id }),
+        DIDVaultContractEvents::RoleGrantedFilter(e) => {
+            return Ok(VaultEventKind::RoleGranted { party: to_checksum(&e.account, None), role: role(e.role)? });
+        }
+        DIDVaultContractEvents::RoleRevokedFilter(e) => {
+            return Ok(VaultEventKind::RoleRevoked { party: to_checksum(&e.account, None), role: role(e.role)? });
+        }
+    };
+    let did_id = kind.did_id().unwrap_or_default();
+    if keccak256(did_id) != id_hash.0 {
+        return Err(format!("id topic does not hash {}", did_id));
+    }
+    Ok(kind)
+}
+
+/// The `Role` of the contract's `enum Role { Admin, Issuer }`.
+fn role(value: u8) -> Result<Role, String> {
+    match value {
+        0 => Ok(Role::Admin),
+        1 => Ok(Role::Issuer),
+        _ => Err(format!("unknown role {}", value)),
+    }
+}
+
+/// Sends the `DIDVault.sol` call matching an ownership-transfer event.
+///
+/// The contract authorizes by `msg.sender`, so the client behind `contract`
+/// must sign as the event's actor. Other event kinds are ignored.
+pub async fn sync_transfer<M: Middleware + 'static>(
+    contract: &DIDVaultContract<M>,
+    kind: &VaultEventKind,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let call = match kind {
+        VaultEventKind::DidTransferProposed { did_id, new_owner } => {
+            contract.transfer_did(did_id.clone(), new_owner.parse::<Address>()?)
+        }
+        VaultEventKind::DidTransferred { did_id, .. } => contract.accept_did_transfer(did_id.clone()),
+        VaultEventKind::DidTransferCancelled { did_id } => contract.cancel_did_transfer(did_id.clone()),
+        _ => return Ok(()),
+    };
+    call.send().await?.await?;
+    Ok(())
+}
+
+/// Sends a `Batch` event as one `multicall` transaction, so it takes effect
+/// on chain all or nothing, like in the vault.
+///
+/// Fails without sending anything if a step has no `DIDVault.sol`
+/// equivalent, which includes every controller and multisig step: the
+/// contract only knows the owner. Other event kinds are ignored.
+pub async fn sync_batch<M: Middleware + 'static>(
+    contract: &DIDVaultContract<M>,
+    kind: &VaultEventKind,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let VaultEventKind::Batch { events } = kind else { return Ok(()) };
+    let mut calls = Vec::with_capacity(events.len());
+    for event in events {
+        let call = match event {
+            VaultEventKind::DidCreated { did_id, metadata, .. } => {
+                contract.create_did(did_id.clone(), metadata.clone())
+            }
+            VaultEventKind::DidUpdated { did_id, metadata } => contract.update_did(did_id.clone(), metadata.clone()),
+            VaultEventKind::DidRevoked { did_id, reason } => contract.revoke_did(did_id.clone(), reason.clone()),
+            VaultEventKind::DidTransferProposed { did_id, new_owner } => {
+                contract.transfer_did(did_id.clone(), new_owner.parse::<Address>()?)
+            }
+            VaultEventKind::DidTransferCancelled { did_id } => contract.cancel_did_transfer(did_id.clone()),
+            VaultEventKind::CredentialIssued {
+                did_id,
+                key,
+                value,
+                not_before: None,
+                expires_at: None,
+                issuer: None,
+                schema: None,
+            } => contract.issue_credential(did_id.clone(), key.clone(), value.clone()),
+            VaultEventKind::CredentialRevoked { did_id, key, .. } => {
+                contract.revoke_credential(did_id.clone(), key.clone())
+            }
+            VaultEventKind::ControllersChanged { did_id, .. }
+            | VaultEventKind::OperationProposed { did_id, .. }
+            | VaultEventKind::OperationApproved { did_id, .. }
+            | VaultEventKind::OperationExecuted { did_id, .. } => {
+                return Err(format!("controller actions on {} cannot be relayed to DIDVault.sol", did_id).into())
+            }
+            other => return Err(format!("{:?} cannot be relayed to DIDVault.sol", other).into()),
+        };
+        calls.push(call.calldata().ok_or("missing calldata")?);
+    }
+    contract.multicall(calls).send().await?.await?;
+    Ok(())
+}
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    resolve_did_key, DIDVault, Did, DidDocument, MemoryStore, Representation, SharedVault, VaultError, VaultStore,
+    VersionQuery, DID, KEY_METHOD, VAULT_METHOD,
+};
+
+/// Error codes of DID resolution, serialized as the spec writes them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionError {
+    InvalidDid,
+    NotFound,
+    MethodNotSupported,
+    Deactivated,
+    InternalError,
+}
+
+impl ResolutionError {
+    pub fn code(self) -> &'static str {
+        match self {
+            ResolutionError::InvalidDid => "invalidDid",
+            ResolutionError::NotFound => "notFound",
+            ResolutionError::MethodNotSupported => "methodNotSupported",
+            ResolutionError::Deactivated => "deactivated",
+            ResolutionError::InternalError => "internalError",
+        }
+    }
+}
+
+impl fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl From<&VaultError> for ResolutionError {
+    fn from(e: &VaultError) -> ResolutionError {
+        match e {
+            VaultError::InvalidDid { .. } | VaultError::InvalidPublicKey { .. } => ResolutionError::InvalidDid,
+            VaultError::UnsupportedDidMethod { .. } => ResolutionError::MethodNotSupported,
+            VaultError::DidNotFound(_) | VaultError::VersionNotFound { .. } => ResolutionError::NotFound,
+            VaultError::DidDeactivated(_) => ResolutionError::Deactivated,
+            _ => ResolutionError::InternalError,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolutionOptions {
+    /// Resolve a past version instead of the current one.
+    #[serde(default)]
+    pub version: Option<VersionQuery>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ResolutionError>,
+}
+
+/// Metadata about the resolved document. Times are XML datetimes in UTC.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deactivated: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+}
+
+/// The result of resolving a DID, per DID Core §7.1. On error there is no
+/// document and `did_resolution_metadata.error` holds the code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionResult {
+    pub did_resolution_metadata: ResolutionMetadata,
+    pub did_document: Option<DidDocument>,
+    pub did_document_metadata: DocumentMetadata,
+}
+
+impl ResolutionResult {
+    pub fn found(document: DidDocument, metadata: DocumentMetadata) -> ResolutionResult {
+        ResolutionResult {
+            did_resolution_metadata: ResolutionMetadata {
+                content_type: Some(Representation::JsonLd.media_type().to_string()),
+                error: None,
+            },
+            did_document: Some(document),
+            did_document_metadata: metadata,
+        }
+    }
+
+    pub fn error(error: ResolutionError) -> ResolutionResult {
+        ResolutionResult {
+            did_resolution_metadata: ResolutionMetadata { content_type: None, error: Some(error) },
+            did_document: None,
+            did_document_metadata: DocumentMetadata::default(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.did_resolution_metadata.error.is_some()
+    }
+}
+
+/// Resolves DIDs of one or more methods. Failures are reported in the
+/// result rather than as `Err`, as resolution metadata carries them.
+pub trait DidResolver: Send + Sync {
+    fn resolve(&self, did: &str, options: &ResolutionOptions) -> ResolutionResult;
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Resolves a `did:dv` DID from this vault. A deactivated DID fails with
+    /// `deactivated` unless a version is asked for; its past versions still
+    /// resolve, with `deactivated` set on the last.
+    pub fn resolve_document(&self, did: &str, chain_id: u64, options: &ResolutionOptions) -> ResolutionResult {
+        if let Err(e) = check_vault_did(did) {
+            return ResolutionResult::error(e);
+        }
+        let found = match options.version {
+            Some(query) => self.get_did_at(did, query),
+            None => self.load_did(did),
+        };
+        resolved(found, chain_id, options)
+    }
+}
+
+/// Fails unless `did` is a well-formed `did:dv` DID.
+fn check_vault_did(did: &str) -> Result<(), ResolutionError> {
+    match Did::parse(did) {
+        Ok(parsed) if parsed.method == VAULT_METHOD => Ok(()),
+        Ok(_) => Err(ResolutionError::MethodNotSupported),
+        Err(_) => Err(ResolutionError::InvalidDid),
+    }
+}
+
+/// The result of resolving to `found`, the record of the DID or of the
+/// version `options` asks for.
+fn resolved(found: Result<DID, VaultError>, chain_id: u64, options: &ResolutionOptions) -> ResolutionResult {
+    let record = match found {
+        Ok(record) => record,
+        Err(e) => return ResolutionResult::error(ResolutionError::from(&e)),
+    };
+    if !record.is_active() && options.version.is_none() {
+        let mut result = ResolutionResult::error(ResolutionError::Deactivated);
+        result.did_document_metadata.deactivated = Some(true);
+        return result;
+    }
+    let metadata = DocumentMetadata {
+        created: Some(xml_datetime(record.created_at)),
+        updated: Some(xml_datetime(record.updated_at)),
+        deactivated: (!record.is_active()).then_some(true),
+        version_id: Some(record.version_id.to_string()),
+    };
+    ResolutionResult::found(DidDocument::from_did(&record, chain_id), metadata)
+}
+
+/// Resolves `did:dv` DIDs from a shared vault, rendering controller
+/// accounts on EVM chain `chain_id`.
+pub struct VaultResolver<S: VaultStore = MemoryStore> {
+    pub vault: SharedVault<S>,
+    pub chain_id: u64,
+}
+
+impl<S: VaultStore> VaultResolver<S> {
+    pub fn new(vault: SharedVault<S>, chain_id: u64) -> VaultResolver<S> {
+        VaultResolver { vault, chain_id }
+    }
+}
+
+impl<S: VaultStore> DidResolver for VaultResolver<S>
+where
+    SharedVault<S>: Send + Sync,
+{
+    fn resolve(&self, did: &str, options: &ResolutionOptions) -> ResolutionResult {
+        // The current version is served from the read view, so resolution
+        // does not wait for writes; only past versions need the vault.
+        if options.version.is_some() {
+            return self.vault.read(|vault| vault.resolve_document(did, self.chain_id, options));
+        }
+        if let Err(e) = check_vault_did(did) {
+            return ResolutionResult::error(e);
+        }
+        let found = self.vault.get_did(did).ok_or_else(|| VaultError::DidNotFound(did.to_string()));
+        resolved(found, self.chain_id, options)
+    }
+}
+
+/// Resolves `did:key` DIDs from the identifier alone. They have a single
+/// version and cannot be deactivated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyResolver;
+
+impl DidResolver for KeyResolver {
+    fn resolve(&self, did: &str, _options: &ResolutionOptions) -> ResolutionResult {
+        match resolve_did_key(did) {
+            Ok(document) => ResolutionResult::found(document, DocumentMetadata::default()),
+            Err(e) => ResolutionResult::error(ResolutionError::from(&e)),
+        }
+    }
+}
+
+/// Dispatches each DID to the resolver registered for its method.
+/// Resolvers can be registered and replaced while the resolver is shared.
+#[derive(Default)]
+pub struct UniversalResolver {
+    resolvers: RwLock<HashMap<String, Arc<dyn DidResolver>>>,
+}
+
+impl UniversalResolver {
+    pub fn new() -> UniversalResolver {
+        UniversalResolver::default()
+    }
+
+    /// A resolver for `did:dv` from `vault` and `did:key`.
+    pub fn with_vault<S: VaultStore>(vault: SharedVault<S>, chain_id: u64) -> UniversalResolver
+    where
+        SharedVault<S>: Send + Sync + 'static,
+    {
+        let resolver = UniversalResolver::new();
+        resolver.register(VAULT_METHOD, Arc::new(VaultResolver::new(vault, chain_id)));
+        resolver.register(KEY_METHOD, Arc::new(KeyResolver));
+        resolver
+    }
+
+    /// Routes `did:<method>:...` to `resolver`, replacing any resolver
+    /// registered for the method before.
+    pub fn register(&self, method: &str, resolver: Arc<dyn DidResolver>) {
+        self.resolvers.write().unwrap_or_else(|e| e.into_inner()).insert(method.to_string(), resolver);
+    }
+
+    pub fn unregister(&self, method: &str) -> bool {
+        self.resolvers.write().unwrap_or_else(|e| e.into_inner()).remove(method).is_some()
+    }
+
+    pub fn methods(&self) -> Vec<String> {
+        let mut methods: Vec<_> = self.resolvers.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+        methods.sort();
+        methods
+    }
+}
+
+impl DidResolver for UniversalResolver {
+    fn resolve(&self, did: &str, options: &ResolutionOptions) -> ResolutionResult {
+        let method = match Did::parse(did) {
+            Ok(parsed) => parsed.method,
+            Err(_) => return ResolutionResult::error(ResolutionError::InvalidDid),
+        };
+        // Release the lock before resolving, so a slow resolver does not
+        // hold up registration.
+        let resolver = self.resolvers.read().unwrap_or_else(|e| e.into_inner()).get(&method).cloned();
+        match resolver {
+            Some(resolver) => resolver.resolve(did, options),
+            None => ResolutionResult::error(ResolutionError::MethodNotSupported),
+        }
+    }
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DDThh:mm:ssZ`.
+fn xml_datetime(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3_600, rem % 3_600 / 60, rem % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{without_blocking, HeldWrite};
+
+    #[test]
+    fn current_version_resolves_during_writes() {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        vault.update_did("did:dv:a", "o", "m").unwrap();
+        vault.create_did("did:dv:gone", "o", "").unwrap();
+        vault.revoke_did("did:dv:gone", "o", "lost").unwrap();
+        let shared = SharedVault::new(vault).unwrap();
+        let resolver = VaultResolver::new(shared.clone(), 1);
+        let expected = shared.read(|v| v.resolve_document("did:dv:a", 1, &ResolutionOptions::default()));
+
+        // The writer holds the vault until finished, so a resolution that
+        // waited on it would never return.
+        let write = HeldWrite::start(&shared, |v| v.create_did("did:dv:b", "o", ""));
+        let [found, gone, missing, other] = without_blocking("resolution during a write", move || {
+            let options = ResolutionOptions::default();
+            [
+                resolver.resolve("did:dv:a", &options),
+                resolver.resolve("did:dv:gone", &options),
+                resolver.resolve("did:dv:missing", &options),
+                resolver.resolve("did:key:abc", &options),
+            ]
+        });
+        write.finish().unwrap();
+
+        assert_eq!(found, expected);
+        assert_eq!(found.did_document_metadata.version_id.as_deref(), Some("2"));
+        assert_eq!(gone.did_resolution_metadata.error, Some(ResolutionError::Deactivated));
+        assert_eq!(gone.did_document_metadata.deactivated, Some(true));
+        assert_eq!(missing.did_resolution_metadata.error, Some(ResolutionError::NotFound));
+        assert_eq!(other.did_resolution_metadata.error, Some(ResolutionError::MethodNotSupported));
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::Authority;
+use crate::{DIDVault, NewCredential, VaultError, VaultEventKind, VaultStore};
+
+/// Points at one version of a registered credential schema.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchemaRef {
+    pub id: String,
+    pub version: u32,
+}
+
+impl SchemaRef {
+    pub fn new(id: &str, version: u32) -> SchemaRef {
+        SchemaRef { id: id.to_string(), version }
+    }
+}
+
+impl fmt::Display for SchemaRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.id, self.version)
+    }
+}
+
+/// A JSON Schema that values of one credential type must satisfy.
+/// Registering under an existing `id` adds its next `version`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialSchema {
+    pub id: String,
+    pub version: u32,
+    pub schema: serde_json::Value,
+    pub registered_by: String,
+    pub registered_at: u64,
+    /// Deprecated versions can no longer be issued against; credentials
+    /// already issued under them are unaffected.
+    #[serde(default)]
+    pub deprecated: bool,
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Registers the next version of `schema_id` and returns its number.
+    pub fn register_schema(&mut self, caller: &str, schema_id: &str, schema: serde_json::Value) -> Result<u32, VaultError> {
+        self.check_admin(caller)?;
+        jsonschema::validator_for(&schema)
+            .map_err(|e| VaultError::InvalidSchema { schema_id: schema_id.to_string(), reason: e.to_string() })?;
+        let version = self.schema_versions(schema_id)?.last().map_or(1, |s| s.version + 1);
+        let kind = VaultEventKind::SchemaRegistered { schema_id: schema_id.to_string(), version, schema };
+        self.commit_as(caller, Authority::Admin, kind)?;
+        Ok(version)
+    }
+
+    pub fn deprecate_schema(&mut self, caller: &str, schema: &SchemaRef) -> Result<(), VaultError> {
+        self.check_admin(caller)?;
+        if self.get_schema(schema)?.deprecated {
+            return Err(VaultError::SchemaDeprecated(schema.to_string()));
+        }
+        let kind = VaultEventKind::SchemaDeprecated { schema_id: schema.id.clone(), version: schema.version };
+        self.commit_as(caller, Authority::Admin, kind)
+    }
+
+    pub fn get_schema(&self, schema: &SchemaRef) -> Result<CredentialSchema, VaultError> {
+        self.store.get_schema(&schema.id, schema.version)?
+            .ok_or_else(|| VaultError::SchemaNotFound(schema.to_string()))
+    }
+
+    /// The newest version of `schema_id` that is not deprecated.
+    pub fn latest_schema(&self, schema_id: &str) -> Result<CredentialSchema, VaultError> {
+        self.schema_versions(schema_id)?.into_iter().rev().find(|s| !s.deprecated)
+            .ok_or_else(|| VaultError::SchemaNotFound(schema_id.to_string()))
+    }
+
+    /// Every version of `schema_id`, oldest first.
+    pub fn schema_versions(&self, schema_id: &str) -> Result<Vec<CredentialSchema>, VaultError> {
+        let mut versions: Vec<_> = self.store.list_schemas()?.into_iter().filter(|s| s.id == schema_id).collect();
+        versions.sort_by_key(|s| s.version);
+        Ok(versions)
+    }
+
+    /// Checks that `cred.value` is JSON satisfying the live schema version
+    /// `schema`.
+    pub(crate) fn check_schema(&self, did_id: &str, cred: &NewCredential, schema: &SchemaRef) -> Result<(), VaultError> {
+        let registered = self.get_schema(schema)?;
+        if registered.deprecated {
+            return Err(VaultError::SchemaDeprecated(schema.to_string()));
+        }
+        let invalid = |reason: String| VaultError::InvalidCredentialValue {
+            did_id: did_id.to_string(),
+            key: cred.key.clone(),
+            reason,
+        };
+        let value: serde_json::Value = serde_json::from_str(&cred.value).map_err(|e| invalid(e.to_string()))?;
+        let validator = jsonschema::validator_for(&registered.schema)
+            .map_err(|e| VaultError::InvalidSchema { schema_id: schema.id.clone(), reason: e.to_string() })?;
+        validator.validate(&value).map_err(|e| invalid(e.to_string()))
+    }
+}
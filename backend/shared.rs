@@ -0,0 +1,217 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+
+use crate::{Clock, Credential, DIDVault, MemoryStore, Validity, VaultError, VaultStore, DID};
+
+/// Number of independently locked partitions of the read view.
+const SHARDS: usize = 64;
+
+/// A DID and its credentials as published to readers.
+#[derive(Debug, Clone)]
+struct Entry {
+    did: DID,
+    credentials: HashMap<String, Credential>,
+}
+
+type Shard = RwLock<HashMap<String, Entry>>;
+
+struct Inner<S: VaultStore> {
+    vault: Mutex<DIDVault<S>>,
+    clock: Arc<dyn Clock>,
+    shards: Vec<Shard>,
+}
+
+/// A cloneable, thread-safe handle to a vault for concurrent request
+/// handlers.
+///
+/// Writes go through `write` one at a time. DID and credential lookups are
+/// served from a read view sharded by DID id; after each write only the
+/// shards of the DIDs named in its journal entries are locked, briefly, to
+/// publish the new state. Lookups therefore never wait for writers, except
+/// for that publish step on a DID hashing to the same shard. A write is
+/// published all at once: every shard it touches is locked before any of
+/// them changes, so no lookup sees part of a batch.
+///
+/// The read view is a full in-memory copy of every DID and credential,
+/// built by `new`, so it takes as much RAM as the vault holds whatever the
+/// store. A sled-backed vault too large for memory should not be wrapped
+/// in one.
+pub struct SharedVault<S: VaultStore = MemoryStore> {
+    inner: Arc<Inner<S>>,
+}
+
+impl<S: VaultStore> Clone for SharedVault<S> {
+    fn clone(&self) -> Self {
+        SharedVault { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<S: VaultStore> SharedVault<S> {
+    pub fn new(vault: DIDVault<S>) -> Result<SharedVault<S>, VaultError> {
+        let mut shards: Vec<HashMap<String, Entry>> = (0..SHARDS).map(|_| HashMap::new()).collect();
+        for did in vault.store.list_dids()? {
+            let credentials = vault.store.get_credentials(&did.id)?.unwrap_or_default();
+            shards[shard_of(&did.id)].insert(did.id.clone(), Entry { did, credentials });
+        }
+        let inner = Inner {
+            clock: Arc::clone(&vault.clock),
+            vault: Mutex::new(vault),
+            shards: shards.into_iter().map(RwLock::new).collect(),
+        };
+        Ok(SharedVault { inner: Arc::new(inner) })
+    }
+
+    /// Runs a mutation against the vault, then publishes every DID it
+    /// touched to readers.
+    pub fn write<T, F>(&self, f: F) -> Result<T, VaultError>
+    where
+        F: FnOnce(&mut DIDVault<S>) -> Result<T, VaultError>,
+    {
+        let mut vault = self.inner.vault.lock().unwrap_or_else(|e| e.into_inner());
+        let before = vault.store.last_event_seq()?;
+        let result = f(&mut vault);
+        // Some operations commit several events and may fail after the
+        // first, so publish whatever made it into the journal.
+        self.publish(&vault, before)?;
+        result
+    }
+
+    /// Runs a read against the vault itself, for queries the read view does
+    /// not cover. Waits for any write in progress.
+    pub fn read<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&DIDVault<S>) -> T,
+    {
+        f(&self.inner.vault.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Returns the DID record, including deactivated tombstones.
+    pub fn get_did(&self, did_id: &str) -> Option<DID> {
+        self.shard(did_id).get(did_id).map(|e| e.did.clone())
+    }
+
+    /// Returns an active DID, or `DidDeactivated` for a tombstone.
+    pub fn resolve_did(&self, did_id: &str) -> Result<DID, VaultError> {
+        let did = self.get_did(did_id).ok_or_else(|| VaultError::DidNotFound(did_id.to_string()))?;
+        if !did.is_active() {
+            return Err(VaultError::DidDeactivated(did_id.to_string()));
+        }
+        Ok(did)
+    }
+
+    /// Returns a credential with its validity as of now.
+    pub fn get_credential(&self, did_id: &str, key: &str) -> Result<(Credential, Validity), VaultError> {
+        let cred = self.shard(did_id).get(did_id).and_then(|e| e.credentials.get(key).cloned())
+            .ok_or_else(|| VaultError::CredentialNotFound { did_id: did_id.to_string(), key: key.to_string() })?;
+        let validity = cred.validity_at(self.inner.clock.now());
+        Ok((cred, validity))
+    }
+
+    /// Credentials the DID has not hidden, sorted by key.
+    pub fn credentials(&self, did_id: &str) -> Vec<Credential> {
+        let mut creds: Vec<_> = self.shard(did_id).get(did_id)
+            .map(|e| e.credentials.values().filter(|c| !c.hidden).cloned().collect())
+            .unwrap_or_default();
+        creds.sort_by(|a, b| a.key.cmp(&b.key));
+        creds
+    }
+
+    fn shard(&self, did_id: &str) -> RwLockReadGuard<'_, HashMap<String, Entry>> {
+        self.inner.shards[shard_of(did_id)].read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Copies the DIDs named by journal entries after `after_seq` from the
+    /// vault into the read view.
+    fn publish(&self, vault: &DIDVault<S>, after_seq: u64) -> Result<(), VaultError> {
+        let mut touched = HashSet::new();
+        let mut cursor = after_seq;
+        loop {
+            let events = vault.store.events_after(cursor, 256)?;
+            let Some(last) = events.last() else { break };
+            cursor = last.seq;
+            touched.extend(events.iter().flat_map(|e| e.kind.did_ids()).map(str::to_string));
+        }
+        let mut entries = Vec::with_capacity(touched.len());
+        for did_id in touched {
+            let entry = match vault.store.get_did(&did_id)? {
+                Some(did) => Some(Entry { did, credentials: vault.store.get_credentials(&did_id)?.unwrap_or_default() }),
+                None => None,
+            };
+            entries.push((did_id, entry));
+        }
+        // Hold every affected shard before changing any of them.
+        let shards: BTreeSet<usize> = entries.iter().map(|(did_id, _)| shard_of(did_id)).collect();
+        let mut guards: BTreeMap<_, _> = shards.into_iter()
+            .map(|i| (i, self.inner.shards[i].write().unwrap_or_else(|e| e.into_inner())))
+            .collect();
+        for (did_id, entry) in entries {
+            let shard = guards.get_mut(&shard_of(&did_id)).expect("shard locked above");
+            match entry {
+                Some(entry) => shard.insert(did_id, entry),
+                None => shard.remove(&did_id),
+            };
+        }
+        Ok(())
+    }
+}
+
+fn shard_of(did_id: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    did_id.hash(&mut hasher);
+    (hasher.finish() % SHARDS as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{vault_with, without_blocking, HeldWrite};
+
+    const DIDS: usize = 256;
+
+    fn vault() -> SharedVault {
+        let ids = (0..DIDS).map(|i| format!("did:dv:r{}", i)).chain(["did:dv:w".to_string()]);
+        SharedVault::new(vault_with("o", ids, &["k"])).unwrap()
+    }
+
+    fn read(vault: &SharedVault, n: usize) {
+        let did_id = format!("did:dv:r{}", n % DIDS);
+        assert!(vault.get_did(&did_id).is_some());
+        assert!(vault.get_credential(&did_id, "k").is_ok());
+    }
+
+    #[test]
+    fn reads_do_not_wait_for_writes() {
+        let vault = vault();
+        let write = HeldWrite::start(&vault, |v| v.update_did("did:dv:w", "o", "m"));
+
+        // The writer holds the vault until finished, so readers that waited
+        // on it would never return.
+        for t in 0..4 {
+            let vault = vault.clone();
+            without_blocking("reads during a write", move || {
+                for n in 0..2_000 {
+                    read(&vault, n + t);
+                }
+            });
+        }
+
+        write.finish().unwrap();
+        assert_eq!(vault.get_did("did:dv:w").unwrap().metadata, "m");
+    }
+
+    #[test]
+    fn readers_share_a_shard() {
+        let vault = vault();
+        // Another reader holds the shard for as long as the lookups run, so
+        // lookups that needed it to themselves would never return.
+        let held = vault.shard("did:dv:r0");
+        let readers: Vec<_> = (0..4).map(|_| vault.clone()).collect();
+        let found = without_blocking("reads of a shard another reader holds", move || {
+            readers.iter().all(|v| v.get_did("did:dv:r0").is_some() && v.get_credential("did:dv:r0", "k").is_ok())
+        });
+        assert!(found);
+        drop(held);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::admin::Role;
+use crate::schemas::CredentialSchema;
+use crate::{Credential, VaultError, DID};
+
+/// Version written into every snapshot. Bump when the layout changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Full vault state as of journal entry `last_seq`.
+///
+/// Restoring a snapshot and replaying the events after `last_seq` yields the
+/// same state as replaying the whole journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultSnapshot {
+    pub version: u32,
+    pub admin: String,
+    pub last_seq: u64,
+    /// Sorted by id. Kept as a list so duplicate ids are detectable on import.
+    pub dids: Vec<DID>,
+    pub credentials: BTreeMap<String, BTreeMap<String, Credential>>,
+    #[serde(default)]
+    pub roles: BTreeMap<String, Vec<Role>>,
+    /// Every recorded version per DID id, oldest first.
+    #[serde(default)]
+    pub did_versions: BTreeMap<String, Vec<DID>>,
+    /// Every schema version, ordered by id and version.
+    #[serde(default)]
+    pub schemas: Vec<CredentialSchema>,
+}
+
+impl VaultSnapshot {
+    pub fn to_json(&self) -> Result<String, VaultError> {
+        serde_json::to_string_pretty(self).map_err(|e| VaultError::Snapshot(e.to_string()))
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>, VaultError> {
+        let mut out = Vec::new();
+        ciborium::ser::into_writer(self, &mut out).map_err(|e| VaultError::Snapshot(e.to_string()))?;
+        Ok(out)
+    }
+
+    pub fn from_json(json: &str) -> Result<VaultSnapshot, VaultError> {
+        let snapshot: VaultSnapshot = serde_json::from_str(json).map_err(|e| VaultError::Snapshot(e.to_string()))?;
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<VaultSnapshot, VaultError> {
+        let snapshot: VaultSnapshot = ciborium::de::from_reader(bytes).map_err(|e| VaultError::Snapshot(e.to_string()))?;
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    /// Rejects unknown format versions and duplicate DID ids.
+    pub fn validate(&self) -> Result<(), VaultError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(VaultError::UnsupportedSnapshotVersion(self.version));
+        }
+        let mut seen = HashSet::new();
+        for did in &self.dids {
+            if !seen.insert(did.id.as_str()) {
+                return Err(VaultError::DidAlreadyExists(did.id.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DIDVault, MemoryStore, NewCredential, Role, VaultStore, VersionQuery};
+
+    fn vault() -> DIDVault {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did("did:dv:a", "alice", "m").unwrap();
+        vault.update_did("did:dv:a", "alice", "m2").unwrap();
+        vault.create_did("did:dv:b", "bob", "").unwrap();
+        vault.issue_credential_with("did:dv:a", "alice", NewCredential::new("k", "v").expires_at(5_000)).unwrap();
+        vault.register_schema("admin", "age", serde_json::json!({ "type": "string" })).unwrap();
+        vault.grant_role("admin", "bob", Role::Issuer).unwrap();
+        vault.revoke_did("did:dv:b", "bob", "gone").unwrap();
+        vault
+    }
+
+    #[test]
+    fn json_round_trip_restores_the_vault() {
+        let vault = vault();
+        let json = vault.export_json().unwrap();
+        let mut imported = DIDVault::import_json(MemoryStore::new(), &json).unwrap();
+        assert_eq!(imported.export_json().unwrap(), json);
+        assert_eq!(imported.get_did_at("did:dv:a", VersionQuery::VersionId(1)).unwrap().metadata, "m");
+        assert!(imported.create_did("did:dv:b", "carol", "").is_err());
+    }
+
+    #[test]
+    fn cbor_round_trip_restores_the_vault() {
+        let vault = vault();
+        let imported = DIDVault::import_cbor(MemoryStore::new(), &vault.export_cbor().unwrap()).unwrap();
+        assert_eq!(imported.export_json().unwrap(), vault.export_json().unwrap());
+        assert_eq!(imported.export_cbor().unwrap(), vault.export_cbor().unwrap());
+    }
+
+    #[test]
+    fn unknown_versions_and_duplicate_dids_are_rejected() {
+        let mut snapshot = vault().snapshot().unwrap();
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        let err = VaultSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap_err();
+        assert_eq!(err, VaultError::UnsupportedSnapshotVersion(SNAPSHOT_VERSION + 1));
+
+        snapshot.version = SNAPSHOT_VERSION;
+        snapshot.dids.push(snapshot.dids[0].clone());
+        let err = VaultSnapshot::from_cbor(&snapshot.to_cbor().unwrap()).unwrap_err();
+        assert_eq!(err, VaultError::DidAlreadyExists("did:dv:a".to_string()));
+    }
+
+    #[test]
+    fn import_needs_an_empty_store() {
+        let vault = vault();
+        let mut store = MemoryStore::new();
+        store.put_did(&vault.resolve_did("did:dv:a").unwrap()).unwrap();
+        let result = DIDVault::import_json(store, &vault.export_json().unwrap());
+        assert!(matches!(result, Err(VaultError::StoreNotEmpty)));
+    }
+}
@@ -0,0 +1,734 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sled::transaction::TransactionError;
+use sled::{IVec, Transactional};
+
+use crate::admin::Role;
+use crate::events::VaultEvent;
+use crate::schemas::CredentialSchema;
+use crate::{Credential, VaultError, DID};
+
+/// Storage backend behind `DIDVault`.
+///
+/// `MemoryStore` keeps everything in process memory; `SledStore` keeps it in
+/// an embedded on-disk database that survives restarts and crashes.
+pub trait VaultStore {
+    fn get_did(&self, did_id: &str) -> Result<Option<DID>, VaultError>;
+    fn put_did(&mut self, did: &DID) -> Result<(), VaultError>;
+    fn remove_did(&mut self, did_id: &str) -> Result<Option<DID>, VaultError>;
+    fn list_dids(&self) -> Result<Vec<DID>, VaultError>;
+    /// Ids of the active DIDs `owner` owns, in order. Kept up to date by
+    /// `put_did` and `remove_did`.
+    fn owned_dids(&self, owner: &str) -> Result<Vec<String>, VaultError>;
+    /// Records `did` under its `version_id`, next to the earlier versions.
+    fn put_did_version(&mut self, did: &DID) -> Result<(), VaultError>;
+    /// Every recorded version of a DID, oldest first.
+    fn list_did_versions(&self, did_id: &str) -> Result<Vec<DID>, VaultError>;
+    /// Drops the versions of a DID after `version_id`, when rolling back.
+    fn remove_did_versions_after(&mut self, did_id: &str, version_id: u64) -> Result<(), VaultError>;
+
+    fn get_credential(&self, did_id: &str, key: &str) -> Result<Option<Credential>, VaultError>;
+    /// All credentials of a DID, or `None` if it holds none.
+    fn get_credentials(&self, did_id: &str) -> Result<Option<HashMap<String, Credential>>, VaultError>;
+    fn put_credential(&mut self, did_id: &str, cred: &Credential) -> Result<(), VaultError>;
+    fn remove_credential(&mut self, did_id: &str, key: &str) -> Result<Option<Credential>, VaultError>;
+    fn remove_credentials(&mut self, did_id: &str) -> Result<(), VaultError>;
+    /// Every stored credential with the id of the DID holding it.
+    fn list_credentials(&self) -> Result<Vec<(String, Credential)>, VaultError>;
+
+    fn get_roles(&self, party: &str) -> Result<Vec<Role>, VaultError>;
+    /// Replaces the roles of `party`; an empty list removes the entry.
+    fn put_roles(&mut self, party: &str, roles: &[Role]) -> Result<(), VaultError>;
+    fn list_roles(&self) -> Result<Vec<(String, Vec<Role>)>, VaultError>;
+
+    fn get_schema(&self, schema_id: &str, version: u32) -> Result<Option<CredentialSchema>, VaultError>;
+    fn put_schema(&mut self, schema: &CredentialSchema) -> Result<(), VaultError>;
+    fn list_schemas(&self) -> Result<Vec<CredentialSchema>, VaultError>;
+
+    fn append_event(&mut self, event: &VaultEvent) -> Result<(), VaultError>;
+    /// Up to `limit` journal entries with `seq > after_seq`, in order.
+    fn events_after(&self, after_seq: u64, limit: usize) -> Result<Vec<VaultEvent>, VaultError>;
+    fn last_event_seq(&self) -> Result<u64, VaultError>;
+    /// Continues journal numbering after `seq`. Used when a store is seeded
+    /// from a snapshot instead of from the events that produced it.
+    fn set_journal_base(&mut self, seq: u64) -> Result<(), VaultError>;
+
+    /// Makes all previous writes durable. Called once per vault operation.
+    fn flush(&mut self) -> Result<(), VaultError> {
+        Ok(())
+    }
+
+    /// Starts grouping writes so that `commit_writes` applies them all at
+    /// once, or `discard_writes` none of them. Reads see them meanwhile.
+    /// Stores that write through, like `MemoryStore`, ignore this.
+    fn begin_writes(&mut self) -> Result<(), VaultError> {
+        Ok(())
+    }
+
+    fn commit_writes(&mut self) -> Result<(), VaultError> {
+        Ok(())
+    }
+
+    fn discard_writes(&mut self) -> Result<(), VaultError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    pub dids: HashMap<String, DID>,
+    /// Active DIDs by owner.
+    pub owners: HashMap<String, BTreeSet<String>>,
+    pub did_versions: HashMap<String, Vec<DID>>,
+    pub credentials: HashMap<String, HashMap<String, Credential>>,
+    pub roles: HashMap<String, Vec<Role>>,
+    pub schemas: HashMap<(String, u32), CredentialSchema>,
+    pub events: Vec<VaultEvent>,
+    pub journal_base: u64,
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+
+    fn unindex_owner(&mut self, did: &DID) {
+        if let Some(ids) = self.owners.get_mut(&did.owner) {
+            ids.remove(&did.id);
+            if ids.is_empty() {
+                self.owners.remove(&did.owner);
+            }
+        }
+    }
+}
+
+impl VaultStore for MemoryStore {
+    fn get_did(&self, did_id: &str) -> Result<Option<DID>, VaultError> {
+        Ok(self.dids.get(did_id).cloned())
+    }
+
+    fn put_did(&mut self, did: &DID) -> Result<(), VaultError> {
+        if let Some(previous) = self.dids.insert(did.id.clone(), did.clone()) {
+            self.unindex_owner(&previous);
+        }
+        if did.is_active() {
+            self.owners.entry(did.owner.clone()).or_default().insert(did.id.clone());
+        }
+        Ok(())
+    }
+
+    fn remove_did(&mut self, did_id: &str) -> Result<Option<DID>, VaultError> {
+        let removed = self.dids.remove(did_id);
+        if let Some(did) = &removed {
+            self.unindex_owner(did);
+        }
+        Ok(removed)
+    }
+
+    fn list_dids(&self) -> Result<Vec<DID>, VaultError> {
+        Ok(self.dids.values().cloned().collect())
+    }
+
+    fn owned_dids(&self, owner: &str) -> Result<Vec<String>, VaultError> {
+        Ok(self.owners.get(owner).map(|ids| ids.iter().cloned().collect()).unwrap_or_default())
+    }
+
+    fn put_did_version(&mut self, did: &DID) -> Result<(), VaultError> {
+        let versions = self.did_versions.entry(did.id.clone()).or_default();
+        versions.retain(|v| v.version_id != did.version_id);
+        let at = versions.partition_point(|v| v.version_id < did.version_id);
+        versions.insert(at, did.clone());
+        Ok(())
+    }
+
+    fn list_did_versions(&self, did_id: &str) -> Result<Vec<DID>, VaultError> {
+        Ok(self.did_versions.get(did_id).cloned().unwrap_or_default())
+    }
+
+    fn remove_did_versions_after(&mut self, did_id: &str, version_id: u64) -> Result<(), VaultError> {
+        if let Some(versions) = self.did_versions.get_mut(did_id) {
+            versions.retain(|v| v.version_id <= version_id);
+            if versions.is_empty() {
+                self.did_versions.remove(did_id);
+            }
+        }
+        Ok(())
+    }
+
+    fn get_credential(&self, did_id: &str, key: &str) -> Result<Option<Credential>, VaultError> {
+        Ok(self.credentials.get(did_id).and_then(|c| c.get(key)).cloned())
+    }
+
+    fn get_credentials(&self, did_id: &str) -> Result<Option<HashMap<String, Credential>>, VaultError> {
+        Ok(self.credentials.get(did_id).cloned())
+    }
+
+    fn put_credential(&mut self, did_id: &str, cred: &Credential) -> Result<(), VaultError> {
+        self.credentials.entry(did_id.to_string()).or_default().insert(cred.key.clone(), cred.clone());
+        Ok(())
+    }
+
+    fn remove_credential(&mut self, did_id: &str, key: &str) -> Result<Option<Credential>, VaultError> {
+        let creds = match self.credentials.get_mut(did_id) {
+            Some(creds) => creds,
+            None => return Ok(None),
+        };
+        let removed = creds.remove(key);
+        if creds.is_empty() {
+            self.credentials.remove(did_id);
+        }
+        Ok(removed)
+    }
+
+    fn remove_credentials(&mut self, did_id: &str) -> Result<(), VaultError> {
+        self.credentials.remove(did_id);
+        Ok(())
+    }
+
+    fn list_credentials(&self) -> Result<Vec<(String, Credential)>, VaultError> {
+        Ok(self.credentials.iter()
+            .flat_map(|(did_id, creds)| creds.values().map(move |c| (did_id.clone(), c.clone())))
+            .collect())
+    }
+
+    fn get_roles(&self, party: &str) -> Result<Vec<Role>, VaultError> {
+        Ok(self.roles.get(party).cloned().unwrap_or_default())
+    }
+
+    fn put_roles(&mut self, party: &str, roles: &[Role]) -> Result<(), VaultError> {
+        if roles.is_empty() {
+            self.roles.remove(party);
+        } else {
+            self.roles.insert(party.to_string(), roles.to_vec());
+        }
+        Ok(())
+    }
+
+    fn list_roles(&self) -> Result<Vec<(String, Vec<Role>)>, VaultError> {
+        Ok(self.roles.iter().map(|(party, roles)| (party.clone(), roles.clone())).collect())
+    }
+
+    fn get_schema(&self, schema_id: &str, version: u32) -> Result<Option<CredentialSchema>, VaultError> {
+        Ok(self.schemas.get(&(schema_id.to_string(), version)).cloned())
+    }
+
+    fn put_schema(&mut self, schema: &CredentialSchema) -> Result<(), VaultError> {
+        self.schemas.insert((schema.id.clone(), schema.version), schema.clone());
+        Ok(())
+    }
+
+    fn list_schemas(&self) -> Result<Vec<CredentialSchema>, VaultError> {
+        Ok(self.schemas.values().cloned().collect())
+    }
+
+    fn append_event(&mut self, event: &VaultEvent) -> Result<(), VaultError> {
+        self.events.push(event.clone());
+        Ok(())
+    }
+
+    fn events_after(&self, after_seq: u64, limit: usize) -> Result<Vec<VaultEvent>, VaultError> {
+        let start = self.events.partition_point(|e| e.seq <= after_seq);
+        Ok(self.events[start..].iter().take(limit).cloned().collect())
+    }
+
+    fn last_event_seq(&self) -> Result<u64, VaultError> {
+        Ok(self.events.last().map(|e| e.seq).unwrap_or(self.journal_base))
+    }
+
+    fn set_journal_base(&mut self, seq: u64) -> Result<(), VaultError> {
+        self.journal_base = seq;
+        Ok(())
+    }
+}
+
+/// Embedded on-disk store backed by sled.
+///
+/// DIDs live in the `dids` tree keyed by id, indexed by owner in the
+/// `owners` tree keyed by `<owner>\0<did_id>` while active, and their past
+/// versions in the
+/// `did_versions` tree keyed by `<did_id>\0<big-endian version_id>`,
+/// credentials in the `credentials` tree keyed by `<did_id>\0<key>`, role
+/// grants in the `roles` tree keyed by party, credential schemas in the
+/// `schemas` tree keyed like DID versions and journal entries in the
+/// `events` tree keyed by big-endian `seq`. Values are JSON. Bookkeeping
+/// such as the journal base lives in the `meta` tree.
+///
+/// Between `begin_writes` and `commit_writes`, writes are held in `pending`
+/// and then applied to every tree in one sled transaction, so a crash never
+/// leaves state changes without the journal entry recording them.
+pub struct SledStore {
+    db: sled::Db,
+    dids: sled::Tree,
+    owners: sled::Tree,
+    did_versions: sled::Tree,
+    credentials: sled::Tree,
+    roles: sled::Tree,
+    schemas: sled::Tree,
+    events: sled::Tree,
+    meta: sled::Tree,
+    pending: Option<Pending>,
+}
+
+/// Writes not yet applied, by tree name; `None` removes the key.
+type Pending = HashMap<IVec, BTreeMap<Vec<u8>, Option<Vec<u8>>>>;
+
+impl SledStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SledStore, VaultError> {
+        let db = sled::open(path)?;
+        let dids = db.open_tree("dids")?;
+        let owners = db.open_tree("owners")?;
+        let did_versions = db.open_tree("did_versions")?;
+        let credentials = db.open_tree("credentials")?;
+        let roles = db.open_tree("roles")?;
+        let schemas = db.open_tree("schemas")?;
+        let events = db.open_tree("events")?;
+        let meta = db.open_tree("meta")?;
+        let mut store = SledStore { db, dids, owners, did_versions, credentials, roles, schemas, events, meta, pending: None };
+        store.index_owners()?;
+        Ok(store)
+    }
+
+    /// Builds the `owners` index for databases written before it existed.
+    fn index_owners(&mut self) -> Result<(), VaultError> {
+        const INDEXED: &[u8] = b"owners_indexed";
+        if self.meta.contains_key(INDEXED)? {
+            return Ok(());
+        }
+        for did in scan_json::<DID>(&self.dids, &None, b"")? {
+            if did.is_active() {
+                self.owners.insert(credential_key(&did.owner, &did.id), Vec::new())?;
+            }
+        }
+        self.meta.insert(INDEXED, Vec::new())?;
+        Ok(())
+    }
+
+    fn trees(&self) -> [&sled::Tree; 8] {
+        [&self.dids, &self.owners, &self.did_versions, &self.credentials, &self.roles, &self.schemas, &self.events, &self.meta]
+    }
+}
+
+fn version_key(did_id: &str, version_id: u64) -> Vec<u8> {
+    let mut key = credential_prefix(did_id);
+    key.extend_from_slice(&version_id.to_be_bytes());
+    key
+}
+
+fn credential_key(did_id: &str, key: &str) -> Vec<u8> {
+    format!("{}\0{}", did_id, key).into_bytes()
+}
+
+fn credential_prefix(did_id: &str) -> Vec<u8> {
+    format!("{}\0", did_id).into_bytes()
+}
+
+fn get(tree: &sled::Tree, pending: &Option<Pending>, key: &[u8]) -> Result<Option<Vec<u8>>, VaultError> {
+    if let Some(write) = pending.as_ref().and_then(|p| p.get(&tree.name())).and_then(|w| w.get(key)) {
+        return Ok(write.clone());
+    }
+    Ok(tree.get(key)?.map(|bytes| bytes.to_vec()))
+}
+
+/// Writes `value` under `key`, or removes the key if `None`, and returns
+/// what was there before.
+fn set(
+    tree: &sled::Tree,
+    pending: &mut Option<Pending>,
+    key: &[u8],
+    value: Option<Vec<u8>>,
+) -> Result<Option<Vec<u8>>, VaultError> {
+    let previous = get(tree, pending, key)?;
+    match (pending, value) {
+        (Some(pending), value) => {
+            pending.entry(tree.name()).or_default().insert(key.to_vec(), value);
+        }
+        (None, Some(value)) => {
+            tree.insert(key, value)?;
+        }
+        (None, None) => {
+            tree.remove(key)?;
+        }
+    }
+    Ok(previous)
+}
+
+/// Up to `limit` entries under `prefix` with keys from `from` on, in key
+/// order, including pending writes.
+fn scan(
+    tree: &sled::Tree,
+    pending: &Option<Pending>,
+    prefix: &[u8],
+    from: &[u8],
+    limit: usize,
+) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, VaultError> {
+    let writes: Vec<_> = pending.as_ref()
+        .and_then(|p| p.get(&tree.name()))
+        .map(|w| w.range(from.to_vec()..).take_while(|(k, _)| k.starts_with(prefix)).collect())
+        .unwrap_or_default();
+    // Every pending removal may hide one stored entry, so read that many
+    // more to still fill the page.
+    let removed = writes.iter().filter(|(_, v)| v.is_none()).count();
+    let mut entries = BTreeMap::new();
+    for entry in tree.range(from..).take(limit.saturating_add(removed)) {
+        let (key, bytes) = entry?;
+        if !key.starts_with(prefix) {
+            break;
+        }
+        entries.insert(key.to_vec(), bytes.to_vec());
+    }
+    for (key, value) in writes {
+        match value {
+            Some(value) => entries.insert(key.clone(), value.clone()),
+            None => entries.remove(key),
+        };
+    }
+    Ok(entries.into_iter().take(limit).collect())
+}
+
+fn get_json<T: DeserializeOwned>(tree: &sled::Tree, pending: &Option<Pending>, key: &[u8]) -> Result<Option<T>, VaultError> {
+    match get(tree, pending, key)? {
+        Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        None => Ok(None),
+    }
+}
+
+fn put_json<T: Serialize>(tree: &sled::Tree, pending: &mut Option<Pending>, key: &[u8], value: &T) -> Result<(), VaultError> {
+    set(tree, pending, key, Some(serde_json::to_vec(value)?))?;
+    Ok(())
+}
+
+fn remove_json<T: DeserializeOwned>(tree: &sled::Tree, pending: &mut Option<Pending>, key: &[u8]) -> Result<Option<T>, VaultError> {
+    match set(tree, pending, key, None)? {
+        Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        None => Ok(None),
+    }
+}
+
+fn scan_json<T: DeserializeOwned>(tree: &sled::Tree, pending: &Option<Pending>, prefix: &[u8]) -> Result<Vec<T>, VaultError> {
+    let mut out = Vec::new();
+    for (_, bytes) in scan(tree, pending, prefix, prefix, usize::MAX)? {
+        out.push(serde_json::from_slice(&bytes)?);
+    }
+    Ok(out)
+}
+
+impl VaultStore for SledStore {
+    fn get_did(&self, did_id: &str) -> Result<Option<DID>, VaultError> {
+        get_json(&self.dids, &self.pending, did_id.as_bytes())
+    }
+
+    fn put_did(&mut self, did: &DID) -> Result<(), VaultError> {
+        if let Some(previous) = self.get_did(&did.id)? {
+            set(&self.owners, &mut self.pending, &credential_key(&previous.owner, &did.id), None)?;
+        }
+        if did.is_active() {
+            set(&self.owners, &mut self.pending, &credential_key(&did.owner, &did.id), Some(Vec::new()))?;
+        }
+        put_json(&self.dids, &mut self.pending, did.id.as_bytes(), did)
+    }
+
+    fn remove_did(&mut self, did_id: &str) -> Result<Option<DID>, VaultError> {
+        let removed: Option<DID> = remove_json(&self.dids, &mut self.pending, did_id.as_bytes())?;
+        if let Some(did) = &removed {
+            set(&self.owners, &mut self.pending, &credential_key(&did.owner, did_id), None)?;
+        }
+        Ok(removed)
+    }
+
+    fn list_dids(&self) -> Result<Vec<DID>, VaultError> {
+        scan_json(&self.dids, &self.pending, b"")
+    }
+
+    fn owned_dids(&self, owner: &str) -> Result<Vec<String>, VaultError> {
+        let prefix = credential_prefix(owner);
+        scan(&self.owners, &self.pending, &prefix, &prefix, usize::MAX)?
+            .into_keys()
+            .map(|key| String::from_utf8(key[prefix.len()..].to_vec()).map_err(|e| VaultError::Storage(e.to_string())))
+            .collect()
+    }
+
+    fn put_did_version(&mut self, did: &DID) -> Result<(), VaultError> {
+        put_json(&self.did_versions, &mut self.pending, &version_key(&did.id, did.version_id), did)
+    }
+
+    fn list_did_versions(&self, did_id: &str) -> Result<Vec<DID>, VaultError> {
+        scan_json(&self.did_versions, &self.pending, &credential_prefix(did_id))
+    }
+
+    fn remove_did_versions_after(&mut self, did_id: &str, version_id: u64) -> Result<(), VaultError> {
+        let from = version_key(did_id, version_id + 1);
+        for (key, _) in scan(&self.did_versions, &self.pending, &credential_prefix(did_id), &from, usize::MAX)? {
+            set(&self.did_versions, &mut self.pending, &key, None)?;
+        }
+        Ok(())
+    }
+
+    fn get_credential(&self, did_id: &str, key: &str) -> Result<Option<Credential>, VaultError> {
+        get_json(&self.credentials, &self.pending, &credential_key(did_id, key))
+    }
+
+    fn get_credentials(&self, did_id: &str) -> Result<Option<HashMap<String, Credential>>, VaultError> {
+        let creds: Vec<Credential> = scan_json(&self.credentials, &self.pending, &credential_prefix(did_id))?;
+        if creds.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(creds.into_iter().map(|c| (c.key.clone(), c)).collect()))
+    }
+
+    fn put_credential(&mut self, did_id: &str, cred: &Credential) -> Result<(), VaultError> {
+        put_json(&self.credentials, &mut self.pending, &credential_key(did_id, &cred.key), cred)
+    }
+
+    fn remove_credential(&mut self, did_id: &str, key: &str) -> Result<Option<Credential>, VaultError> {
+        remove_json(&self.credentials, &mut self.pending, &credential_key(did_id, key))
+    }
+
+    fn remove_credentials(&mut self, did_id: &str) -> Result<(), VaultError> {
+        let prefix = credential_prefix(did_id);
+        for (key, _) in scan(&self.credentials, &self.pending, &prefix, &prefix, usize::MAX)? {
+            set(&self.credentials, &mut self.pending, &key, None)?;
+        }
+        Ok(())
+    }
+
+    fn list_credentials(&self) -> Result<Vec<(String, Credential)>, VaultError> {
+        let mut out = Vec::new();
+        for (key, bytes) in scan(&self.credentials, &self.pending, b"", b"", usize::MAX)? {
+            let did_id = key.split(|b| *b == 0).next().unwrap_or_default();
+            out.push((String::from_utf8_lossy(did_id).into_owned(), serde_json::from_slice(&bytes)?));
+        }
+        Ok(out)
+    }
+
+    fn get_roles(&self, party: &str) -> Result<Vec<Role>, VaultError> {
+        Ok(get_json(&self.roles, &self.pending, party.as_bytes())?.unwrap_or_default())
+    }
+
+    fn put_roles(&mut self, party: &str, roles: &[Role]) -> Result<(), VaultError> {
+        if roles.is_empty() {
+            set(&self.roles, &mut self.pending, party.as_bytes(), None)?;
+            return Ok(());
+        }
+        put_json(&self.roles, &mut self.pending, party.as_bytes(), &roles)
+    }
+
+    fn list_roles(&self) -> Result<Vec<(String, Vec<Role>)>, VaultError> {
+        let mut out = Vec::new();
+        for (party, bytes) in scan(&self.roles, &self.pending, b"", b"", usize::MAX)? {
+            out.push((String::from_utf8_lossy(&party).into_owned(), serde_json::from_slice(&bytes)?));
+        }
+        Ok(out)
+    }
+
+    fn get_schema(&self, schema_id: &str, version: u32) -> Result<Option<CredentialSchema>, VaultError> {
+        get_json(&self.schemas, &self.pending, &version_key(schema_id, version as u64))
+    }
+
+    fn put_schema(&mut self, schema: &CredentialSchema) -> Result<(), VaultError> {
+        put_json(&self.schemas, &mut self.pending, &version_key(&schema.id, schema.version as u64), schema)
+    }
+
+    fn list_schemas(&self) -> Result<Vec<CredentialSchema>, VaultError> {
+        scan_json(&self.schemas, &self.pending, b"")
+    }
+
+    fn append_event(&mut self, event: &VaultEvent) -> Result<(), VaultError> {
+        put_json(&self.events, &mut self.pending, &event.seq.to_be_bytes(), event)
+    }
+
+    fn events_after(&self, after_seq: u64, limit: usize) -> Result<Vec<VaultEvent>, VaultError> {
+        let mut out = Vec::new();
+        let Some(start) = after_seq.checked_add(1) else { return Ok(out) };
+        for (_, bytes) in scan(&self.events, &self.pending, b"", &start.to_be_bytes(), limit)? {
+            out.push(serde_json::from_slice(&bytes)?);
+        }
+        Ok(out)
+    }
+
+    fn last_event_seq(&self) -> Result<u64, VaultError> {
+        // Journal entries are only ever appended, so a pending one is the
+        // last if there is any.
+        let pending = self.pending.as_ref()
+            .and_then(|p| p.get(&self.events.name()))
+            .and_then(|w| w.values().next_back().cloned().flatten());
+        let last = match pending {
+            Some(bytes) => Some(bytes),
+            None => self.events.last()?.map(|(_, bytes)| bytes.to_vec()),
+        };
+        match last {
+            Some(bytes) => Ok(serde_json::from_slice::<VaultEvent>(&bytes)?.seq),
+            None => Ok(get_json(&self.meta, &self.pending, b"journal_base")?.unwrap_or(0)),
+        }
+    }
+
+    fn set_journal_base(&mut self, seq: u64) -> Result<(), VaultError> {
+        put_json(&self.meta, &mut self.pending, b"journal_base", &seq)
+    }
+
+    fn flush(&mut self) -> Result<(), VaultError> {
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn begin_writes(&mut self) -> Result<(), VaultError> {
+        self.pending.get_or_insert_with(Pending::new);
+        Ok(())
+    }
+
+    fn commit_writes(&mut self) -> Result<(), VaultError> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        let trees = self.trees();
+        let committed = trees[..].transaction(|views| {
+            for (tree, view) in trees.iter().zip(views) {
+                for (key, value) in pending.get(&tree.name()).into_iter().flatten() {
+                    match value {
+                        Some(value) => view.insert(key.as_slice(), value.as_slice())?,
+                        None => view.remove(key.as_slice())?,
+                    };
+                }
+            }
+            Ok(())
+        });
+        committed.map_err(|e: TransactionError<()>| match e {
+            TransactionError::Storage(e) => VaultError::from(e),
+            TransactionError::Abort(()) => VaultError::Storage("transaction aborted".to_string()),
+        })
+    }
+
+    fn discard_writes(&mut self) -> Result<(), VaultError> {
+        self.pending = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{BatchOp, DIDVault, DidAction, EventQuery};
+
+    /// A fresh database directory, removed when dropped.
+    struct TempDb(PathBuf);
+
+    impl TempDb {
+        fn new(name: &str) -> TempDb {
+            let path = std::env::temp_dir().join(format!("did-vault-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            TempDb(path)
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn did(id: &str) -> DID {
+        let mut vault = DIDVault::new("admin");
+        vault.create_did(id, "o", "").unwrap();
+        vault.load_did(id).unwrap()
+    }
+
+    #[test]
+    fn grouped_writes_are_seen_and_dropped_together() {
+        let db = TempDb::new("discard");
+        let mut store = SledStore::open(&db.0).unwrap();
+        store.put_did(&did("did:dv:a")).unwrap();
+
+        store.begin_writes().unwrap();
+        store.put_did(&did("did:dv:b")).unwrap();
+        store.remove_did("did:dv:a").unwrap();
+        let ids: Vec<_> = store.list_dids().unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, ["did:dv:b"]);
+        store.discard_writes().unwrap();
+
+        let ids: Vec<_> = store.list_dids().unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, ["did:dv:a"]);
+    }
+
+    #[test]
+    fn operations_reach_the_trees() {
+        let db = TempDb::new("commit");
+        let mut vault = DIDVault::with_store("admin", SledStore::open(&db.0).unwrap());
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        vault.issue_credential("did:dv:a", "o", "k", "v").unwrap();
+        let store = vault.store;
+        assert!(store.pending.is_none());
+        assert!(store.dids.contains_key("did:dv:a").unwrap());
+        assert!(store.credentials.contains_key(credential_key("did:dv:a", "k")).unwrap());
+        assert_eq!(store.events.len(), 2);
+    }
+
+    #[test]
+    fn failed_batch_leaves_no_trace() {
+        let db = TempDb::new("batch");
+        let mut vault = DIDVault::with_store("admin", SledStore::open(&db.0).unwrap());
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        let ops = vec![
+            BatchOp::CreateDid { did_id: "did:dv:b".to_string(), metadata: String::new() },
+            BatchOp::Did { did_id: "did:dv:a".to_string(), action: DidAction::Update { metadata: "m".to_string() } },
+            BatchOp::Did { did_id: "did:dv:c".to_string(), action: DidAction::Update { metadata: "m".to_string() } },
+        ];
+        assert!(vault.apply_batch("o", ops).is_err());
+
+        let store = vault.store;
+        assert!(store.pending.is_none());
+        assert!(store.get_did("did:dv:b").unwrap().is_none());
+        assert_eq!(store.get_did("did:dv:a").unwrap().unwrap().metadata, "");
+        assert_eq!(store.list_did_versions("did:dv:a").unwrap().len(), 1);
+        assert_eq!(store.last_event_seq().unwrap(), 1);
+    }
+
+    #[test]
+    fn pending_journal_entries_are_paged() {
+        let db = TempDb::new("events");
+        let mut vault = DIDVault::with_store("admin", SledStore::open(&db.0).unwrap());
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        vault.store.begin_writes().unwrap();
+        let mut event = vault.store.events_after(0, 1).unwrap().remove(0);
+        event.seq = 2;
+        vault.store.append_event(&event).unwrap();
+        assert_eq!(vault.store.last_event_seq().unwrap(), 2);
+        let seqs: Vec<_> = vault.store.events_after(0, 10).unwrap().iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [1, 2]);
+        assert_eq!(vault.store.events_after(1, 1).unwrap()[0].seq, 2);
+        vault.store.discard_writes().unwrap();
+        assert_eq!(vault.store.last_event_seq().unwrap(), 1);
+    }
+
+    #[test]
+    fn history_after_the_last_possible_seq_is_empty() {
+        let db = TempDb::new("max-seq");
+        let mut vault = DIDVault::with_store("admin", SledStore::open(&db.0).unwrap());
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        let page = vault.history(&EventQuery::all(10).after(u64::MAX)).unwrap();
+        assert!(page.events.is_empty());
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn owner_index_follows_dids() {
+        let db = TempDb::new("owners");
+        let mut vault = DIDVault::with_store("admin", SledStore::open(&db.0).unwrap());
+        vault.create_did("did:dv:a", "o", "").unwrap();
+        vault.create_did("did:dv:b", "o", "").unwrap();
+        vault.revoke_did("did:dv:a", "o", "").unwrap();
+        vault.transfer_did("did:dv:b", "o", "p").unwrap();
+        vault.accept_transfer("did:dv:b", "p").unwrap();
+        assert!(vault.store.owned_dids("o").unwrap().is_empty());
+        assert_eq!(vault.store.owned_dids("p").unwrap(), ["did:dv:b"]);
+
+        vault.store.begin_writes().unwrap();
+        vault.store.remove_did("did:dv:b").unwrap();
+        assert!(vault.store.owned_dids("p").unwrap().is_empty());
+        vault.store.discard_writes().unwrap();
+        assert_eq!(vault.store.owned_dids("p").unwrap(), ["did:dv:b"]);
+    }
+}
@@ -0,0 +1,87 @@
+//! Fixtures shared by the unit tests.
+
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::{generate_key_did, genesis_message, DIDVault, KeyType, Limits, PublicKey, SharedVault, VaultError};
+
+/// A vault in which `owner` holds `dids`, each with a credential of value
+/// `"v"` under every one of `keys`.
+pub(crate) fn vault_with<I>(owner: &str, dids: I, keys: &[&str]) -> DIDVault
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let dids: Vec<_> = dids.into_iter().collect();
+    let limits = Limits { max_dids_per_owner: dids.len().max(Limits::default().max_dids_per_owner), ..Limits::default() };
+    let mut vault = DIDVault::new("admin").with_limits(limits);
+    for did_id in &dids {
+        vault.create_did(did_id.as_ref(), owner, "").unwrap();
+        for key in keys {
+            vault.issue_credential(did_id.as_ref(), owner, key, "v").unwrap();
+        }
+    }
+    vault
+}
+
+/// Creates the self-certifying DID of the Ed25519 key derived from `seed`
+/// for `owner`, and returns its id and the key.
+pub(crate) fn create_key_did(vault: &mut DIDVault, owner: &str, seed: u8) -> (String, PublicKey) {
+    let signer = SigningKey::from_bytes(&[seed; 32]);
+    let key = PublicKey::new(KeyType::Ed25519, signer.verifying_key().as_bytes()).unwrap();
+    let signature = signer.sign(&genesis_message(&generate_key_did(&key), owner));
+    let did_id = vault.create_did_from_key(owner, &key, &signature.to_bytes(), "").unwrap();
+    (did_id, key)
+}
+
+/// A `SharedVault::write` parked while holding the vault, until `finish`.
+pub(crate) struct HeldWrite {
+    release: Sender<()>,
+    writer: JoinHandle<Result<(), VaultError>>,
+}
+
+impl HeldWrite {
+    /// Starts a write that runs `f` once released, and returns as soon as
+    /// the write holds the vault.
+    pub(crate) fn start<F>(vault: &SharedVault, f: F) -> HeldWrite
+    where
+        F: FnOnce(&mut DIDVault) -> Result<(), VaultError> + Send + 'static,
+    {
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release, release_rx) = mpsc::channel::<()>();
+        let vault = vault.clone();
+        let writer = thread::spawn(move || {
+            vault.write(|v| {
+                entered_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+                f(v)
+            })
+        });
+        entered_rx.recv().unwrap();
+        HeldWrite { release, writer }
+    }
+
+    /// Lets the write run and returns its result.
+    pub(crate) fn finish(self) -> Result<(), VaultError> {
+        self.release.send(()).unwrap();
+        self.writer.join().unwrap()
+    }
+}
+
+/// Runs `f` on another thread and returns its result, failing the test if
+/// it has not finished within ten seconds, e.g. because it waited on a
+/// `HeldWrite` or on a lock the test holds.
+pub(crate) fn without_blocking<T, F>(what: &str, f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (done_tx, done_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = done_tx.send(f());
+    });
+    done_rx.recv_timeout(Duration::from_secs(10)).unwrap_or_else(|_| panic!("{} blocked", what))
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Credential, DIDVault, VaultError, VaultStore};
+
+/// Whether a credential can be relied on at a given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Validity {
+    Valid,
+    NotYetValid,
+    Expired,
+    Revoked,
+}
+
+impl Credential {
+    /// Revocation wins over the validity window.
+    pub fn validity_at(&self, at: u64) -> Validity {
+        if !self.is_active() {
+            Validity::Revoked
+        } else if self.not_before.is_some_and(|t| at < t) {
+            Validity::NotYetValid
+        } else if self.expires_at.is_some_and(|t| at >= t) {
+            Validity::Expired
+        } else {
+            Validity::Valid
+        }
+    }
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// Returns a credential, including revoked and expired ones, with its
+    /// validity as of now.
+    pub fn get_credential(&self, did_id: &str, key: &str) -> Result<(Credential, Validity), VaultError> {
+        let cred = self.store.get_credential(did_id, key)?
+            .ok_or_else(|| VaultError::CredentialNotFound { did_id: did_id.to_string(), key: key.to_string() })?;
+        let validity = cred.validity_at(self.now());
+        Ok((cred, validity))
+    }
+
+    /// Credentials that are not revoked and expire within the next `within`
+    /// seconds, soonest first, with the id of the DID holding each.
+    pub fn expiring_credentials(&self, within: u64) -> Result<Vec<(String, Credential)>, VaultError> {
+        let now = self.now();
+        let mut expiring: Vec<_> = self.store.list_credentials()?.into_iter()
+            .filter(|(_, c)| c.is_active() && c.expires_at.is_some_and(|t| t > now && t - now <= within))
+            .collect();
+        expiring.sort_by_key(|(_, c)| c.expires_at);
+        Ok(expiring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockClock, NewCredential};
+
+    fn vault(clock: &MockClock) -> DIDVault {
+        let mut vault = DIDVault::new("admin").with_clock(clock.clone());
+        vault.create_did("did:dv:a", "a", "").unwrap();
+        vault
+    }
+
+    #[test]
+    fn validity_window_is_inclusive_then_exclusive() {
+        let clock = MockClock::new(100);
+        let mut vault = vault(&clock);
+        vault.issue_credential_with("did:dv:a", "a", NewCredential::new("k", "v").not_before(200).expires_at(300)).unwrap();
+        let validity = |vault: &DIDVault| vault.get_credential("did:dv:a", "k").unwrap().1;
+        assert_eq!(validity(&vault), Validity::NotYetValid);
+        clock.set(200);
+        assert_eq!(validity(&vault), Validity::Valid);
+        clock.set(299);
+        assert_eq!(validity(&vault), Validity::Valid);
+        clock.set(300);
+        assert_eq!(validity(&vault), Validity::Expired);
+    }
+
+    #[test]
+    fn revocation_wins_over_the_window() {
+        let clock = MockClock::new(100);
+        let mut vault = vault(&clock);
+        vault.issue_credential_with("did:dv:a", "a", NewCredential::new("k", "v").not_before(200)).unwrap();
+        vault.revoke_credential("did:dv:a", "a", "k").unwrap();
+        assert_eq!(vault.get_credential("did:dv:a", "k").unwrap().1, Validity::Revoked);
+    }
+
+    #[test]
+    fn empty_windows_are_rejected() {
+        let clock = MockClock::new(100);
+        let mut vault = vault(&clock);
+        let cred = NewCredential::new("k", "v").not_before(300).expires_at(300);
+        let err = vault.issue_credential_with("did:dv:a", "a", cred).unwrap_err();
+        assert_eq!(err, VaultError::InvalidValidity { did_id: "did:dv:a".to_string(), key: "k".to_string() });
+    }
+
+    #[test]
+    fn only_expired_credentials_can_be_renewed() {
+        let clock = MockClock::new(100);
+        let mut vault = vault(&clock);
+        vault.issue_credential_with("did:dv:a", "a", NewCredential::new("k", "v1").expires_at(200)).unwrap();
+        let err = vault.issue_credential("did:dv:a", "a", "k", "v2").unwrap_err();
+        assert!(matches!(err, VaultError::CredentialAlreadyExists { .. }));
+        clock.set(200);
+        vault.issue_credential("did:dv:a", "a", "k", "v2").unwrap();
+        let (cred, validity) = vault.get_credential("did:dv:a", "k").unwrap();
+        assert_eq!((cred.value.as_str(), validity), ("v2", Validity::Valid));
+    }
+
+    #[test]
+    fn expiring_credentials_are_listed_soonest_first() {
+        let clock = MockClock::new(100);
+        let mut vault = vault(&clock);
+        for (key, expires_at) in [("late", 190), ("soon", 150), ("past", 100), ("far", 1_000)] {
+            vault.issue_credential_with("did:dv:a", "a", NewCredential::new(key, "v").expires_at(expires_at)).unwrap();
+        }
+        vault.issue_credential("did:dv:a", "a", "forever", "v").unwrap();
+        let keys: Vec<_> = vault.expiring_credentials(100).unwrap().into_iter().map(|(_, c)| c.key).collect();
+        assert_eq!(keys, ["soon", "late"]);
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{DIDVault, VaultError, VaultStore, DID};
+
+/// Selects a past version of a DID, like the `versionId` and `versionTime`
+/// DID resolution parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionQuery {
+    VersionId(u64),
+    /// The version in effect at this unix timestamp.
+    Time(u64),
+}
+
+impl<S: VaultStore> DIDVault<S> {
+    /// The DID as it was at the selected version. Deactivated DIDs resolve
+    /// too, so signatures made before deactivation can still be checked.
+    pub fn get_did_at(&self, did_id: &str, query: VersionQuery) -> Result<DID, VaultError> {
+        let found = self.did_versions(did_id)?.into_iter().rev().find(|did| match query {
+            VersionQuery::VersionId(id) => did.version_id == id,
+            VersionQuery::Time(at) => did.updated_at <= at,
+        });
+        found.ok_or_else(|| VaultError::VersionNotFound { did_id: did_id.to_string(), query })
+    }
+
+    /// Every version of a DID, oldest first. A DID written before versions
+    /// were recorded has its current record as its only version.
+    pub fn did_versions(&self, did_id: &str) -> Result<Vec<DID>, VaultError> {
+        let versions = self.store.list_did_versions(did_id)?;
+        if !versions.is_empty() {
+            return Ok(versions);
+        }
+        Ok(vec![self.load_did(did_id)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DidStatus, MockClock};
+
+    fn vault() -> DIDVault {
+        let clock = MockClock::new(100);
+        let mut vault = DIDVault::new("admin").with_clock(clock.clone());
+        vault.create_did("did:dv:a", "alice", "v1").unwrap();
+        clock.set(200);
+        vault.update_did("did:dv:a", "alice", "v2").unwrap();
+        clock.set(250);
+        vault.transfer_did("did:dv:a", "alice", "bob").unwrap();
+        clock.set(300);
+        vault.accept_transfer("did:dv:a", "bob").unwrap();
+        clock.set(400);
+        vault.revoke_did("did:dv:a", "bob", "retired").unwrap();
+        vault
+    }
+
+    #[test]
+    fn versions_are_found_by_id() {
+        let vault = vault();
+        let at = |id| vault.get_did_at("did:dv:a", VersionQuery::VersionId(id)).unwrap();
+        assert_eq!((at(1).metadata.as_str(), at(1).owner.as_str()), ("v1", "alice"));
+        assert_eq!((at(2).metadata.as_str(), at(2).owner.as_str()), ("v2", "alice"));
+        assert_eq!((at(3).metadata.as_str(), at(3).owner.as_str()), ("v2", "bob"));
+        assert_eq!(at(4).status, DidStatus::Deactivated);
+        assert_eq!(vault.did_versions("did:dv:a").unwrap().len(), 4);
+
+        let err = vault.get_did_at("did:dv:a", VersionQuery::VersionId(5)).unwrap_err();
+        assert_eq!(err, VaultError::VersionNotFound { did_id: "did:dv:a".to_string(), query: VersionQuery::VersionId(5) });
+    }
+
+    #[test]
+    fn versions_are_found_by_time() {
+        let vault = vault();
+        let at = |t| vault.get_did_at("did:dv:a", VersionQuery::Time(t)).map(|did| did.version_id);
+        assert_eq!(at(100), Ok(1));
+        assert_eq!(at(199), Ok(1));
+        // A pending transfer is not a new version.
+        assert_eq!(at(250), Ok(2));
+        assert_eq!(at(300), Ok(3));
+        assert_eq!(at(1_000), Ok(4));
+        assert!(matches!(at(99), Err(VaultError::VersionNotFound { .. })));
+    }
+
+    #[test]
+    fn unknown_dids_have_no_versions() {
+        let vault = vault();
+        let err = vault.get_did_at("did:dv:nope", VersionQuery::VersionId(1)).unwrap_err();
+        assert_eq!(err, VaultError::DidNotFound("did:dv:nope".to_string()));
+    }
+}